@@ -0,0 +1,351 @@
+/*
+    Entry points of the packing engine for rust callers.
+    Inputs and results are plain rust types, the wasm bindings only convert from and to javascript values.
+    Inputs are validated before packing, the first problem is returned as a PackingError with the path of the field.
+    validate_input lists all problems of the input.
+*/
+use crate::bin_packing::bin::{Bin, DLBinWithPackedOrders, RawBin};
+use crate::bin_packing::error::PackingError;
+use crate::bin_packing::item::{Item, RawPackedItem};
+use crate::bin_packing::options::{DimensionLessOptions, FloorPlanOptions, PackingOptions};
+use crate::bin_packing::order::{DimensionLessOrder, Order, RawOrder};
+use crate::bin_packing::progress::SolverMonitor;
+use crate::bin_packing::result::{
+    DLAlternativesResult, DLBinPackingResult, DLPackingResult, FloorPlanResult,
+    MultiBinPackingResult, PackingResult, Violation,
+};
+use crate::bin_packing::solver;
+use crate::bin_packing::solver::search::{
+    get_best_fitting_bin_for_item_vector, get_best_multi_bin_packing,
+};
+use crate::bin_packing::sort_bin_list_by_weight;
+use crate::bin_packing::validation::{ValidationIssue, Validator};
+
+// A cancelled run returns an error instead of its partial result
+fn check_cancelled(monitor: &SolverMonitor) -> Result<(), PackingError> {
+    match monitor.is_cancelled() {
+        true => Err(PackingError::Cancelled),
+        false => Ok(()),
+    }
+}
+
+// Every item of every order, each with quantity 1
+fn get_item_list(raw_orders: &[RawOrder]) -> Vec<Item> {
+    raw_orders
+        .iter()
+        .flat_map(|order| order.create_order_from_raw_order().items)
+        .collect()
+}
+
+// Bins sorted by max weight, smallest first
+fn get_sorted_bin_list(raw_bins: &[RawBin]) -> Vec<Bin> {
+    let mut bins: Vec<Bin> = raw_bins.iter().map(|bin| bin.convert_to_bin()).collect();
+    sort_bin_list_by_weight(&mut bins, true);
+    bins
+}
+
+/*
+    Smallest bin that holds all items of the orders.
+    If no bin holds all items, the result holds the fullest bin and the unpacked items.
+    The monitor receives progress events and may cancel the run, SolverMonitor::none() for neither.
+*/
+pub fn get_smallest_fitting_bin_for_order_list(
+    raw_orders: &[RawOrder],
+    raw_bins: &[RawBin],
+    options: &PackingOptions,
+    monitor: &SolverMonitor,
+) -> Result<PackingResult, PackingError> {
+    let mut validator = Validator::new();
+    validator.validate_raw_orders(raw_orders);
+    validator.validate_raw_bins(raw_bins);
+    validator.validate_packing_options(options);
+    validator.into_result()?;
+
+    let orders: Vec<Order> = raw_orders
+        .iter()
+        .map(|order| order.create_order_from_raw_order())
+        .collect();
+    let total_order_weight: f32 = orders.iter().map(|order| order.get_order_weight()).sum();
+    let total_order_volume: f32 = orders.iter().map(|order| order.get_order_volume()).sum();
+
+    // item list is sorted by the solver, once for every ordering in the options
+    let result = get_best_fitting_bin_for_item_vector(
+        &get_item_list(raw_orders),
+        &get_sorted_bin_list(raw_bins),
+        total_order_weight,
+        total_order_volume,
+        options,
+        monitor,
+    );
+    check_cancelled(monitor)?;
+    Ok(result)
+}
+
+// Packs the orders into as many bins as needed, bins from the bin list can be used more than once
+pub fn pack_order_list_into_multiple_bins(
+    raw_orders: &[RawOrder],
+    raw_bins: &[RawBin],
+    options: &PackingOptions,
+    monitor: &SolverMonitor,
+) -> Result<MultiBinPackingResult, PackingError> {
+    let mut validator = Validator::new();
+    validator.validate_raw_orders(raw_orders);
+    validator.validate_raw_bins(raw_bins);
+    validator.validate_packing_options(options);
+    validator.into_result()?;
+
+    let result = get_best_multi_bin_packing(
+        &get_item_list(raw_orders),
+        &get_sorted_bin_list(raw_bins),
+        options,
+        monitor,
+    );
+    check_cancelled(monitor)?;
+    Ok(result)
+}
+
+// Places every item of the orders as a pallet onto the floor of the bin
+pub fn plan_pallet_floor(
+    raw_orders: &[RawOrder],
+    raw_bin: &RawBin,
+    options: &FloorPlanOptions,
+) -> Result<FloorPlanResult, PackingError> {
+    let mut validator = Validator::new();
+    validator.validate_raw_orders(raw_orders);
+    validator.validate_raw_bin(raw_bin, "bin");
+    validator.validate_floor_plan_options(options);
+    validator.into_result()?;
+
+    Ok(solver::floor::plan_pallet_floor(
+        raw_bin.convert_to_bin(),
+        get_item_list(raw_orders),
+        options,
+    ))
+}
+
+// Packs max additional dimensionless orders into dimensionless bins that may already have packed orders
+pub fn pack_max_dimensionless_orders(
+    dl_bins_with_packed_orders: Vec<DLBinWithPackedOrders>,
+    additional_orders: Vec<DimensionLessOrder>,
+    options: &DimensionLessOptions,
+) -> Result<DLPackingResult, PackingError> {
+    let mut validator = Validator::new();
+    validator.validate_dimensionless_bins(&dl_bins_with_packed_orders, "bins");
+    validator.validate_dimensionless_orders(&additional_orders, "orders");
+    validator.into_result()?;
+    Ok(solver::dimensionless::pack_max_dimensionless_orders(
+        dl_bins_with_packed_orders,
+        additional_orders,
+        options,
+    ))
+}
+
+// Alternative selections of additional dimensionless orders for every bin
+pub fn get_dimensionless_alternatives(
+    dl_bins_with_packed_orders: Vec<DLBinWithPackedOrders>,
+    additional_orders: Vec<DimensionLessOrder>,
+    options: &DimensionLessOptions,
+) -> Result<DLAlternativesResult, PackingError> {
+    let mut validator = Validator::new();
+    validator.validate_dimensionless_bins(&dl_bins_with_packed_orders, "bins");
+    validator.validate_dimensionless_orders(&additional_orders, "orders");
+    validator.into_result()?;
+    Ok(solver::dimensionless::get_dimensionless_alternatives(
+        dl_bins_with_packed_orders,
+        additional_orders,
+        options,
+    ))
+}
+
+// Empty dimensionless bins to open for the orders at the lowest total cost
+pub fn pack_orders_into_dimensionless_bins(
+    bin_types: Vec<DLBinWithPackedOrders>,
+    orders: Vec<DimensionLessOrder>,
+    options: &DimensionLessOptions,
+) -> Result<DLBinPackingResult, PackingError> {
+    let mut validator = Validator::new();
+    validator.validate_dimensionless_bins(&bin_types, "bin_types");
+    validator.validate_dimensionless_orders(&orders, "orders");
+    validator.into_result()?;
+    Ok(solver::dimensionless::pack_orders_into_dimensionless_bins(
+        bin_types, orders, options,
+    ))
+}
+
+// All problems of 3D orders and bins with their paths, empty if the input can be packed
+pub fn validate_input(raw_orders: &[RawOrder], raw_bins: &[RawBin]) -> Vec<ValidationIssue> {
+    let mut validator = Validator::new();
+    validator.validate_raw_orders(raw_orders);
+    validator.validate_raw_bins(raw_bins);
+    validator.issues.iter().map(ValidationIssue::new).collect()
+}
+
+/*
+    Checks a packed bin independently of the solver, e.g. after the plan was edited by hand.
+    Returns every broken constraint, an empty list for a valid plan.
+*/
+pub fn verify_plan(
+    raw_bin: &RawBin,
+    raw_items: &[RawPackedItem],
+    options: &PackingOptions,
+) -> Result<Vec<Violation>, PackingError> {
+    let mut validator = Validator::new();
+    validator.validate_raw_bin(raw_bin, "bin");
+    validator.validate_raw_packed_items(raw_items);
+    validator.validate_packing_options(options);
+    validator.into_result()?;
+
+    Ok(solver::verify::verify_packed_bin(
+        raw_bin.convert_to_bin(),
+        raw_items
+            .iter()
+            .map(|item| item.convert_to_item())
+            .collect(),
+        options,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bin_packing::item::RawItem;
+    use crate::bin_packing::progress::CancellationToken;
+    use std::thread;
+
+    fn get_raw_bins() -> Vec<RawBin> {
+        vec![RawBin {
+            name: "van".to_string(),
+            depth: 200.0,
+            width: 100.0,
+            height: 100.0,
+            max_weight: 500.0,
+            cog_envelope: None,
+            axles: None,
+        }]
+    }
+
+    fn get_raw_orders(quantity: i32) -> Vec<RawOrder> {
+        vec![RawOrder {
+            name: "order".to_string(),
+            items: vec![RawItem {
+                name: "box".to_string(),
+                quantity,
+                depth: 50.0,
+                width: 50.0,
+                height: 50.0,
+                weight: 10.0,
+                max_top_load: None,
+            }],
+            orientable: None,
+            stackable: None,
+        }]
+    }
+
+    #[test]
+    fn native_callers_get_plain_results() {
+        let result = get_smallest_fitting_bin_for_order_list(
+            &get_raw_orders(8),
+            &get_raw_bins(),
+            &PackingOptions::default(),
+            &SolverMonitor::none(),
+        )
+        .unwrap();
+        assert!(result.is_complete());
+        assert_eq!(result.bin.as_ref().unwrap().packed_items.len(), 8);
+
+        // 20 boxes need two vans of 16
+        let result = pack_order_list_into_multiple_bins(
+            &get_raw_orders(20),
+            &get_raw_bins(),
+            &PackingOptions::default(),
+            &SolverMonitor::none(),
+        )
+        .unwrap();
+        assert_eq!(result.bins.len(), 2);
+        assert!(result.unpacked_items.is_empty());
+
+        // results serialize for a backend as they do for javascript
+        let json = serde_json::to_value(&result).unwrap();
+        assert_eq!(json["bins"].as_array().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn cancellation_token_is_shared_between_threads() {
+        let token = CancellationToken::new();
+        let other_token = token.clone();
+        thread::spawn(move || other_token.cancel()).join().unwrap();
+
+        let result = pack_order_list_into_multiple_bins(
+            &get_raw_orders(20),
+            &get_raw_bins(),
+            &PackingOptions::default(),
+            &SolverMonitor::new(&(), &token),
+        );
+        assert_eq!(result.unwrap_err(), PackingError::Cancelled);
+    }
+
+    #[test]
+    fn numbers_that_are_not_a_number_give_an_error_with_their_path() {
+        let mut raw_orders = get_raw_orders(2);
+        raw_orders[0].items[0].weight = f32::NAN;
+        let result = get_smallest_fitting_bin_for_order_list(
+            &raw_orders,
+            &get_raw_bins(),
+            &PackingOptions::default(),
+            &SolverMonitor::none(),
+        );
+        let error = result.unwrap_err();
+        assert_eq!(error.get_code(), "invalid_value");
+        assert_eq!(error.get_path(), Some("orders[0].items[0].weight"));
+
+        let mut raw_bins = get_raw_bins();
+        raw_bins[0].width = f32::NAN;
+        let result = pack_order_list_into_multiple_bins(
+            &get_raw_orders(2),
+            &raw_bins,
+            &PackingOptions::default(),
+            &SolverMonitor::none(),
+        );
+        let error = result.unwrap_err();
+        assert_eq!(error.get_code(), "invalid_dimension");
+        assert_eq!(error.get_path(), Some("bins[0].width"));
+
+        let bins: Vec<DLBinWithPackedOrders> =
+            serde_json::from_value(serde_json::json!([{ "id": "bin", "max_weight": 10.0 }]))
+                .unwrap();
+        let mut orders: Vec<DimensionLessOrder> =
+            serde_json::from_value(serde_json::json!([{ "id": "order", "weight": 1.0 }])).unwrap();
+        orders[0].weight = f64::NAN;
+        let error = pack_max_dimensionless_orders(bins, orders, &DimensionLessOptions::default())
+            .err()
+            .unwrap();
+        assert_eq!(error.get_code(), "invalid_value");
+        assert_eq!(error.get_path(), Some("orders[0].weight"));
+    }
+
+    #[test]
+    fn validate_input_lists_every_issue() {
+        let mut raw_orders = get_raw_orders(1);
+        raw_orders[0].items[0].quantity = -1;
+        let mut raw_bins = get_raw_bins();
+        raw_bins[0].depth = 0.0;
+        let issues = validate_input(&raw_orders, &raw_bins);
+        assert_eq!(
+            issues,
+            [
+                ValidationIssue {
+                    code: "invalid_value",
+                    path: Some("orders[0].items[0].quantity".to_string()),
+                    message: "orders[0].items[0].quantity: must be a non-negative number, got -1"
+                        .to_string(),
+                },
+                ValidationIssue {
+                    code: "invalid_dimension",
+                    path: Some("bins[0].depth".to_string()),
+                    message: "bins[0].depth: dimension must be a positive number, got 0"
+                        .to_string(),
+                },
+            ]
+        );
+    }
+}
@@ -1,5 +1,7 @@
 use crate::bin_packing::item::Item;
+use crate::bin_packing::result::LoadingMeters;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 use super::order::DimensionLessOrder;
 
@@ -14,7 +16,18 @@ pub struct Bin {
     pub height: f32,
     #[serde(skip_serializing)]
     pub max_weight: f32,
+    #[serde(skip_serializing)]
+    pub cog_envelope: Option<CogEnvelope>,
+    // sorted by position
+    #[serde(skip_serializing)]
+    pub axles: Vec<Axle>,
     pub packed_items: Vec<Item>,
+    // load on each axle, in the order of axles
+    pub axle_loads: Vec<f32>,
+    // centre of gravity of the packed load, None for an empty bin
+    pub center_of_gravity: Option<[f32; 3]>,
+    // floor length taken by the load, None unless requested in the options
+    pub loading_meters: Option<LoadingMeters>,
 }
 
 impl Bin {
@@ -37,6 +50,59 @@ impl Bin {
         }
         weight
     }
+
+    // Weighted mean of the packed item centres, in bin coordinates (width, height, depth)
+    pub fn get_center_of_gravity(&self) -> Option<[f32; 3]> {
+        let total_weight = self.get_packed_items_weight();
+        if total_weight <= 0.0 {
+            return None;
+        }
+        let mut moment = [0.0; 3];
+        for item in &self.packed_items {
+            let dimension = item.get_rotated_dimension(&item.rotation);
+            for axis in 0..3 {
+                moment[axis] += item.weight * (item.position[axis] + dimension[axis] / 2.0);
+            }
+        }
+        Some(moment.map(|m| m / total_weight))
+    }
+
+    // Check if the centre of gravity lies within the allowed envelope around the bin centre
+    pub fn is_balanced(&self) -> bool {
+        let (Some(envelope), Some(center_of_gravity)) =
+            (&self.cog_envelope, self.get_center_of_gravity())
+        else {
+            return true;
+        };
+        let bin_dimension = [self.width, self.height, self.depth];
+        let max_deviations = [envelope.width, envelope.height, envelope.depth];
+        (0..3).all(|axis| match max_deviations[axis] {
+            Some(max_deviation) => {
+                (center_of_gravity[axis] - bin_dimension[axis] / 2.0).abs()
+                    <= max_deviation * bin_dimension[axis]
+            }
+            None => true,
+        })
+    }
+}
+
+/*
+    Allowed deviation of the centre of gravity from the bin centre,
+    as a share of the bin dimension along each axis (e.g. depth: 0.1 is within 10% lengthwise)
+    Axes without a value are not checked
+*/
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CogEnvelope {
+    pub width: Option<f32>,
+    pub height: Option<f32>,
+    pub depth: Option<f32>,
+}
+
+// Axle of a truck bin, position is measured along the depth of the bin from its front
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Axle {
+    pub position: f32,
+    pub max_load: f32,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -46,28 +112,51 @@ pub struct RawBin {
     pub width: f32,
     pub height: f32,
     pub max_weight: f32,
+    pub cog_envelope: Option<CogEnvelope>,
+    pub axles: Option<Vec<Axle>>,
 }
 
 impl RawBin {
     pub fn convert_to_bin(&self) -> Bin {
+        let mut axles = self.axles.clone().unwrap_or_default();
+        axles.sort_by(|a, b| a.position.total_cmp(&b.position));
         Bin {
             name: self.name.clone(),
             width: self.width,
             depth: self.depth,
             height: self.height,
             max_weight: self.max_weight,
+            cog_envelope: self.cog_envelope.clone(),
+            axle_loads: vec![0.0; axles.len()],
+            axles,
             packed_items: Vec::new(),
+            center_of_gravity: None,
+            loading_meters: None,
         }
     }
 }
 
 // WARNING: used only for DimensionLessOrder
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct DLBinWithPackedOrders {
     pub id: String,
     pub max_weight: f64,
     pub max_volume: Option<f64>,
+    // capacity of named resources, e.g. pallet spaces or cold chain slots
+    #[serde(default)]
+    pub max_resources: BTreeMap<String, f64>,
+    // typical weight per volume unit of the load, estimates missing order volumes
+    pub density: Option<f64>,
+    // ids of additional orders that must be loaded into this bin
+    #[serde(default)]
+    pub must_include: Vec<String>,
+    // cost of opening a bin of this type, 1.0 if missing, so the fewest bins are opened
+    pub cost: Option<f64>,
+    // bins of this type that can be opened, unlimited if missing
+    pub count: Option<usize>,
+    #[serde(default)]
     pub packed_orders: Vec<DimensionLessOrder>,
     // Field to store the result
+    #[serde(default)]
     pub additional_packed_orders: Vec<DimensionLessOrder>,
 }
@@ -0,0 +1,190 @@
+/*
+    Errors returned by the api instead of panicking on bad input.
+    Every error has a code and, where it comes from a field of the input, the path of that field,
+    e.g. "orders[3].items[1].width".
+*/
+use serde::Serialize;
+use std::fmt;
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(tag = "code", rename_all = "snake_case")]
+pub enum PackingError {
+    // the payload could not be read, e.g. a missing field or a string where a number is expected
+    InvalidInput {
+        path: String,
+        message: String,
+    },
+    // a list that needs at least one entry is empty
+    EmptyInput {
+        path: String,
+    },
+    // zero, negative or not a number
+    InvalidDimension {
+        path: String,
+        value: f64,
+    },
+    // negative or not a number
+    InvalidValue {
+        path: String,
+        value: f64,
+    },
+    // names and ids must be unique, first_path is where the name is used first
+    DuplicateName {
+        path: String,
+        name: String,
+        first_path: String,
+    },
+    // empty or containing "@", which separates order and item in item names
+    InvalidName {
+        path: String,
+        name: String,
+    },
+    // constraints that no plan can satisfy, e.g. a centre of gravity envelope without extent
+    InfeasibleConstraint {
+        path: String,
+        message: String,
+    },
+    // the run was cancelled through its monitor
+    Cancelled,
+    // the result could not be converted for the caller
+    SerializationFailed {
+        message: String,
+    },
+}
+
+impl PackingError {
+    pub fn get_code(&self) -> &'static str {
+        match self {
+            PackingError::InvalidInput { .. } => "invalid_input",
+            PackingError::EmptyInput { .. } => "empty_input",
+            PackingError::InvalidDimension { .. } => "invalid_dimension",
+            PackingError::InvalidValue { .. } => "invalid_value",
+            PackingError::DuplicateName { .. } => "duplicate_name",
+            PackingError::InvalidName { .. } => "invalid_name",
+            PackingError::InfeasibleConstraint { .. } => "infeasible_constraint",
+            PackingError::Cancelled => "cancelled",
+            PackingError::SerializationFailed { .. } => "serialization_failed",
+        }
+    }
+
+    pub fn get_path(&self) -> Option<&str> {
+        match self {
+            PackingError::InvalidInput { path, .. }
+            | PackingError::EmptyInput { path }
+            | PackingError::InvalidDimension { path, .. }
+            | PackingError::InvalidValue { path, .. }
+            | PackingError::DuplicateName { path, .. }
+            | PackingError::InvalidName { path, .. }
+            | PackingError::InfeasibleConstraint { path, .. } => Some(path),
+            PackingError::Cancelled | PackingError::SerializationFailed { .. } => None,
+        }
+    }
+}
+
+impl fmt::Display for PackingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PackingError::InvalidInput { path, message } => write!(f, "{}: {}", path, message),
+            PackingError::EmptyInput { path } => write!(f, "{}: must not be empty", path),
+            PackingError::InvalidDimension { path, value } => {
+                write!(
+                    f,
+                    "{}: dimension must be a positive number, got {}",
+                    path, value
+                )
+            }
+            PackingError::InvalidValue { path, value } => {
+                write!(f, "{}: must be a non-negative number, got {}", path, value)
+            }
+            PackingError::DuplicateName {
+                path,
+                name,
+                first_path,
+            } => write!(
+                f,
+                "{}: \"{}\" is already used at {}",
+                path, name, first_path
+            ),
+            PackingError::InvalidName { path, name } => {
+                write!(
+                    f,
+                    "{}: \"{}\" must not be empty or contain \"@\"",
+                    path, name
+                )
+            }
+            PackingError::InfeasibleConstraint { path, message } => {
+                write!(f, "{}: {}", path, message)
+            }
+            PackingError::Cancelled => write!(f, "the run was cancelled"),
+            PackingError::SerializationFailed { message } => {
+                write!(f, "result could not be serialized: {}", message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PackingError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_errors() -> Vec<PackingError> {
+        let path = "orders[0].items[0].width".to_string();
+        vec![
+            PackingError::InvalidInput {
+                path: path.clone(),
+                message: "invalid type".to_string(),
+            },
+            PackingError::EmptyInput {
+                path: "orders".to_string(),
+            },
+            PackingError::InvalidDimension {
+                path: path.clone(),
+                value: 0.0,
+            },
+            PackingError::InvalidValue {
+                path: path.clone(),
+                value: -1.0,
+            },
+            PackingError::DuplicateName {
+                path: "orders[1].name".to_string(),
+                name: "order".to_string(),
+                first_path: "orders[0].name".to_string(),
+            },
+            PackingError::InvalidName {
+                path: "orders[0].name".to_string(),
+                name: "a@b".to_string(),
+            },
+            PackingError::InfeasibleConstraint {
+                path: "options.min_support_ratio".to_string(),
+                message: "must be a share between 0.0 and 1.0".to_string(),
+            },
+            PackingError::Cancelled,
+            PackingError::SerializationFailed {
+                message: "unsupported value".to_string(),
+            },
+        ]
+    }
+
+    #[test]
+    fn serialized_errors_carry_their_code_and_path() {
+        for error in get_errors() {
+            let json = serde_json::to_value(&error).unwrap();
+            assert_eq!(json["code"], error.get_code());
+            match error.get_path() {
+                Some(path) => assert_eq!(json["path"], path),
+                None => assert!(json.get("path").is_none()),
+            }
+        }
+    }
+
+    #[test]
+    fn messages_start_with_the_path() {
+        for error in get_errors() {
+            if let Some(path) = error.get_path() {
+                assert!(error.to_string().starts_with(path), "{error}");
+            }
+        }
+    }
+}
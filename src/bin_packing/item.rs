@@ -8,6 +8,41 @@ pub struct RawItem {
     pub width: f32,
     pub height: f32,
     pub weight: f32,
+    // maximum weight that may rest on top of the item
+    pub max_top_load: Option<f32>,
+}
+
+// Item of a packed plan given back for verification, with its original dimensions
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RawPackedItem {
+    pub name: String,
+    pub depth: f32,
+    pub width: f32,
+    pub height: f32,
+    pub weight: f32,
+    pub max_top_load: Option<f32>,
+    pub orientable: Option<bool>,
+    pub stackable: Option<bool>,
+    pub rotation: String,
+    pub position: [f32; 3],
+}
+
+impl RawPackedItem {
+    pub fn convert_to_item(&self) -> Item {
+        Item {
+            name: self.name.clone(),
+            width: self.width,
+            depth: self.depth,
+            height: self.height,
+            weight: self.weight,
+            orientable: self.orientable,
+            stackable: self.stackable,
+            max_top_load: self.max_top_load,
+            top_load: 0.0,
+            rotation: self.rotation.clone(),
+            position: self.position,
+        }
+    }
 }
 
 /*
@@ -30,6 +65,11 @@ pub struct Item {
     pub orientable: Option<bool>,
     #[serde(skip_serializing)]
     pub stackable: Option<bool>,
+    #[serde(skip_serializing)]
+    pub max_top_load: Option<f32>,
+    // weight currently resting on top of the item, through the whole stack
+    #[serde(skip_serializing)]
+    pub top_load: f32,
     pub rotation: String,
     pub position: [f32; 3],
 }
@@ -39,6 +79,22 @@ impl Item {
         self.width * self.depth * self.height
     }
 
+    pub fn get_longest_edge(&self) -> f32 {
+        self.width.max(self.depth).max(self.height)
+    }
+
+    pub fn get_footprint_area(&self) -> f32 {
+        self.width * self.depth
+    }
+
+    // Maximum weight that may rest on the item, a non stackable item carries nothing
+    pub fn get_top_load_limit(&self) -> Option<f32> {
+        match self.stackable {
+            Some(false) => Some(0.0),
+            _ => self.max_top_load,
+        }
+    }
+
     pub fn get_rotated_dimension(&self, key: &str) -> [f32; 3] {
         match key {
             "RT_WHD" => [self.width, self.height, self.depth],
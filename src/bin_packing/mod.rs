@@ -1,13 +1,41 @@
+pub mod api;
 pub mod bin;
+pub mod error;
 pub mod item;
+pub mod options;
 pub mod order;
+pub mod progress;
+pub mod result;
 pub mod solver;
+pub mod validation;
 
 use bin::Bin;
+use item::Item;
+use options::ItemOrdering;
 
-pub fn sort_bin_list_by_weight(bin_list: &mut Vec<Bin>, ascending: bool) {
+pub fn sort_bin_list_by_weight(bin_list: &mut [Bin], ascending: bool) {
     match ascending {
-        true => bin_list.sort_by(|a, b| a.max_weight.partial_cmp(&b.max_weight).unwrap()),
-        false => bin_list.sort_by(|a, b| b.max_weight.partial_cmp(&a.max_weight).unwrap()),
+        true => bin_list.sort_by(|a, b| a.max_weight.total_cmp(&b.max_weight)),
+        false => bin_list.sort_by(|a, b| b.max_weight.total_cmp(&a.max_weight)),
+    }
+}
+
+// Sorts items descending by the ordering key
+pub fn sort_item_list(item_list: &mut [Item], ordering: ItemOrdering) {
+    match ordering {
+        ItemOrdering::Weight => item_list.sort_by(|a, b| b.weight.total_cmp(&a.weight)),
+        ItemOrdering::Volume => item_list.sort_by(|a, b| b.get_volume().total_cmp(&a.get_volume())),
+        ItemOrdering::LongestEdge => {
+            item_list.sort_by(|a, b| b.get_longest_edge().total_cmp(&a.get_longest_edge()))
+        }
+        ItemOrdering::FootprintArea => {
+            item_list.sort_by(|a, b| b.get_footprint_area().total_cmp(&a.get_footprint_area()))
+        }
+        ItemOrdering::Height => item_list.sort_by(|a, b| b.height.total_cmp(&a.height)),
+        ItemOrdering::WeightThenVolume => item_list.sort_by(|a, b| {
+            b.weight
+                .total_cmp(&a.weight)
+                .then(b.get_volume().total_cmp(&a.get_volume()))
+        }),
     }
 }
@@ -0,0 +1,256 @@
+use serde::Deserialize;
+
+// Solver settings passed from the frontend, every field is optional
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct PackingOptions {
+    // minimum share (0.0 - 1.0) of an item's base that must rest on the bin floor or on items below
+    // 0.0 only rejects items that rest on nothing
+    pub min_support_ratio: f32,
+    pub placement_engine: PlacementEngine,
+    pub placement_policy: PlacementPolicy,
+    pub item_ordering: ItemOrdering,
+    // orderings tried one after another, the best plan wins; empty runs item_ordering only
+    pub multi_start: Vec<ItemOrdering>,
+    // decides which plan is best between multi start runs
+    pub objective: PlanObjective,
+    // improvement search run after the constructive solver, None skips it
+    pub improvement: Option<ImprovementOptions>,
+    // length units per meter of the bin and item dimensions, e.g. 100.0 for centimeters
+    // None skips the loading meters of the packed bins
+    pub units_per_meter: Option<f32>,
+}
+
+// How candidate positions for the next item are generated
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PlacementEngine {
+    // three corners of every placed item, a used pivot is dropped
+    #[default]
+    Pivots,
+    // corners of every placed item projected onto item faces and bin walls
+    ExtremePoints,
+}
+
+// How the position of the next item is chosen among the open pivots
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PlacementPolicy {
+    // first open pivot and rotation that takes the item
+    #[default]
+    FirstFit,
+    // lowest, then front most, then left most position
+    BottomLeftBack,
+    // lowest top face of the placed item
+    LowestHeight,
+    // largest area touching packed items and bin walls
+    BestContactArea,
+    // smallest free space left around the item up to the next obstacles
+    MinResidualSpace,
+}
+
+// Order in which items are offered to the solver, every key sorts descending
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ItemOrdering {
+    #[default]
+    Weight,
+    Volume,
+    LongestEdge,
+    FootprintArea,
+    Height,
+    WeightThenVolume,
+}
+
+/*
+    Plans that pack every item always win, then the smaller bin for single bin plans
+    and fewer bins for multi bin plans.
+    The objective breaks the remaining ties.
+*/
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PlanObjective {
+    // packed volume over bin volume
+    #[default]
+    Utilisation,
+    PackedVolume,
+    PackedWeight,
+    PackedCount,
+}
+
+/*
+    Simulated annealing over the item sequence and item rotations.
+    The search stops at whichever budget runs out first.
+*/
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct ImprovementOptions {
+    pub max_iterations: u32,
+    // wall clock budget in milliseconds, None runs all iterations
+    pub time_limit_ms: Option<f64>,
+    // same seed and input give the same plan
+    pub seed: u64,
+    // relative loss of the objective that is accepted with probability 1/e at the start
+    pub initial_temperature: f32,
+    // temperature is multiplied by this value after every iteration
+    pub cooling_rate: f32,
+}
+
+impl Default for ImprovementOptions {
+    fn default() -> ImprovementOptions {
+        ImprovementOptions {
+            max_iterations: 200,
+            time_limit_ms: None,
+            seed: 1,
+            initial_temperature: 0.05,
+            cooling_rate: 0.99,
+        }
+    }
+}
+
+// Settings for planning pallets onto a truck floor, every field is optional
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct FloorPlanOptions {
+    // length units per meter of the bin and item dimensions, e.g. 100.0 for centimeters
+    // None skips the loading meters of the bin and the orders
+    pub units_per_meter: Option<f32>,
+    // pallets with the same footprint are stacked into columns, as far as their top load allows
+    pub stacking: bool,
+}
+
+impl Default for FloorPlanOptions {
+    fn default() -> FloorPlanOptions {
+        FloorPlanOptions {
+            units_per_meter: None,
+            stacking: true,
+        }
+    }
+}
+
+// Settings for packing dimensionless orders, every field is optional
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct DimensionLessOptions {
+    // every additional order goes to at most one bin
+    pub exclusive: bool,
+    pub objective: DimensionLessObjective,
+    // how orders without volume are handled by bins with a volume limit
+    pub missing_volume: MissingVolumePolicy,
+    // pairs of order categories that never share a bin, a category paired with itself allows one order per bin
+    pub conflicts: Vec<(String, String)>,
+    // used by get_dimensionless_alternatives only
+    pub alternatives: AlternativesOptions,
+    // used by pack_max_dimensionless_orders, alternatives report if their state limit was reached
+    pub approximation: ApproximationOptions,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct ApproximationOptions {
+    pub mode: SolveMode,
+    // Auto switches to the approximation once the exact knapsack keeps more states than this
+    pub max_exact_states: usize,
+    // Exact keeps at most this many states, above it the result is the better of the most valuable
+    // states kept and the approximation, and is only reported as exact if it reaches the bound.
+    // States that cannot beat the best known selection are dropped, so an integer or value objective
+    // usually stays below the limit, e.g. a few seconds for 500 orders and 3 bins. Weights or volumes with
+    // fractions as objective give nearly every sum a state of its own, the result is then close to the
+    // bound but rarely proven exact, and takes about a minute for 500 orders and 3 bins at the default.
+    pub max_states: usize,
+    // steps of the multiplier search of the approximation, each step tries one greedy selection
+    pub iterations: usize,
+    // nodes of the search assigning orders to bins in exclusive mode, not used by Approximate
+    pub max_assignment_nodes: usize,
+}
+
+impl Default for ApproximationOptions {
+    fn default() -> ApproximationOptions {
+        ApproximationOptions {
+            mode: SolveMode::Auto,
+            max_exact_states: 10_000,
+            max_states: 50_000,
+            iterations: 100,
+            max_assignment_nodes: 1_000_000,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SolveMode {
+    // exact knapsack while the number of states stays small, approximation otherwise
+    #[default]
+    Auto,
+    Exact,
+    // greedy knapsack guided by a Lagrangian relaxation, the result reports the gap to its bound
+    Approximate,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct AlternativesOptions {
+    pub mode: AlternativesMode,
+    // number of selections listed by top k, volume limits swept by the Pareto front
+    pub count: usize,
+    // states kept by every search after every order, more states are dropped and the alternatives
+    // are reported as incomplete. Orders with whole weights and volumes share states and stay complete
+    // for a few hundred orders, fractional ones give nearly every sum a state of its own
+    pub max_states: usize,
+}
+
+impl Default for AlternativesOptions {
+    fn default() -> AlternativesOptions {
+        AlternativesOptions {
+            mode: AlternativesMode::TopK,
+            count: 5,
+            max_states: 10_000,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AlternativesMode {
+    // best selections by the objective, best first
+    #[default]
+    TopK,
+    // selections no other selection beats by being at least as heavy with less volume, heaviest first
+    ParetoFront,
+}
+
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MissingVolumePolicy {
+    // order is not offered to bins with a volume limit
+    #[default]
+    Reject,
+    // order takes no volume
+    Zero,
+    // volume is estimated as weight / density of the bin, rejected if the bin has no density
+    Density,
+}
+
+// What the knapsack maximises for each bin
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum DimensionLessObjective {
+    #[default]
+    Weight,
+    Volume,
+    // number of orders
+    Count,
+    // value given on the orders, e.g. revenue or priority
+    Value,
+    // sum of the above, each multiplied by its factor
+    Weighted(ObjectiveFactors),
+}
+
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct ObjectiveFactors {
+    pub weight: f64,
+    pub volume: f64,
+    pub count: f64,
+    pub value: f64,
+}
@@ -1,5 +1,7 @@
 use crate::bin_packing::item::{Item, RawItem};
+use crate::bin_packing::options::DimensionLessObjective;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct RawOrder {
@@ -29,13 +31,15 @@ impl RawOrder {
                     position: [0.0, 0.0, 0.0],
                     orientable: self.orientable,
                     stackable: self.stackable,
+                    max_top_load: item.max_top_load,
+                    top_load: 0.0,
                 });
             }
         }
-        return Order {
+        Order {
             name: self.name.clone(),
             items,
-        };
+        }
     }
 }
 
@@ -51,7 +55,7 @@ impl Order {
         for item in &self.items {
             total_volume += item.get_volume();
         }
-        return total_volume;
+        total_volume
     }
 
     pub fn get_order_weight(&self) -> f32 {
@@ -59,14 +63,43 @@ impl Order {
         for item in &self.items {
             total_weight += item.weight;
         }
-        return total_weight;
+        total_weight
     }
 }
 
-// DimensionLessOrder has items with no given dimensions, hence only weight and/or volume is considered
+// DimensionLessOrder has items with no given dimensions, hence only weight, volume and named resources are considered
 #[derive(Serialize, Deserialize, Clone)]
 pub struct DimensionLessOrder {
     pub id: String,
     pub weight: f64,
     pub volume: Option<f64>,
+    // e.g. revenue, priority score or urgency, used by the value objective
+    pub value: Option<f64>,
+    // usage of named resources, matched against the max_resources of a bin
+    #[serde(default)]
+    pub resources: BTreeMap<String, f64>,
+    // orders of the same group are loaded all-or-none into one bin, e.g. split shipments of one customer
+    pub group: Option<String>,
+    // category matched against the conflict pairs of the options, e.g. "food" or "chemicals"
+    pub category: Option<String>,
+}
+
+impl DimensionLessOrder {
+    // Value the knapsack maximises, missing volume or value counts as 0
+    pub fn get_objective_value(&self, objective: DimensionLessObjective) -> f64 {
+        let volume = self.volume.unwrap_or(0.0);
+        let value = self.value.unwrap_or(0.0);
+        match objective {
+            DimensionLessObjective::Weight => self.weight,
+            DimensionLessObjective::Volume => volume,
+            DimensionLessObjective::Count => 1.0,
+            DimensionLessObjective::Value => value,
+            DimensionLessObjective::Weighted(factors) => {
+                factors.weight * self.weight
+                    + factors.volume * volume
+                    + factors.count
+                    + factors.value * value
+            }
+        }
+    }
 }
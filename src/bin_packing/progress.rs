@@ -0,0 +1,134 @@
+/*
+    Progress reporting and cancellation of long solver runs.
+    The solver reports events to a ProgressSink and polls a Cancellation between items.
+    Closures implement both traits, so native callers pass e.g. |event| println!("{:?}", event) and || false.
+*/
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ProgressEvent {
+    // packing into a bin starts, bin_index counts the bins tried or opened by the run.
+    // Only reported by a single constructive run, a search over several runs reports Percentage and BestSoFar
+    BinStarted {
+        bin_index: usize,
+        bin_name: String,
+    },
+    // an item is placed, item_index is its position in the item list
+    ItemPlaced {
+        bin_index: usize,
+        item_index: usize,
+        item_name: String,
+    },
+    // share of the search done, 0.0 - 100.0
+    Percentage {
+        percent: f32,
+    },
+    // packed volume over bin volume of the best plan found so far
+    BestSoFar {
+        utilisation: f32,
+    },
+}
+
+pub trait ProgressSink {
+    fn report(&self, event: &ProgressEvent);
+}
+
+impl<F: Fn(&ProgressEvent)> ProgressSink for F {
+    fn report(&self, event: &ProgressEvent) {
+        self(event)
+    }
+}
+
+// No progress reporting
+impl ProgressSink for () {
+    fn report(&self, _event: &ProgressEvent) {}
+}
+
+/*
+    Polled by the solver, once cancelled the run stops with what it has packed so far,
+    items it did not get to are reported as cancelled. The api returns PackingError::Cancelled instead.
+*/
+pub trait Cancellation {
+    fn is_cancelled(&self) -> bool;
+}
+
+impl<F: Fn() -> bool> Cancellation for F {
+    fn is_cancelled(&self) -> bool {
+        self()
+    }
+}
+
+// Never cancelled
+impl Cancellation for () {
+    fn is_cancelled(&self) -> bool {
+        false
+    }
+}
+
+// Cancellation shared between threads, clones cancel the same run
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    pub fn new() -> CancellationToken {
+        CancellationToken::default()
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+}
+
+impl Cancellation for CancellationToken {
+    fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+// Progress sink and cancellation of one solver run
+#[derive(Clone, Copy)]
+pub struct SolverMonitor<'a> {
+    pub progress: &'a dyn ProgressSink,
+    pub cancellation: &'a dyn Cancellation,
+}
+
+impl<'a> SolverMonitor<'a> {
+    pub fn new(
+        progress: &'a dyn ProgressSink,
+        cancellation: &'a dyn Cancellation,
+    ) -> SolverMonitor<'a> {
+        SolverMonitor {
+            progress,
+            cancellation,
+        }
+    }
+
+    // No progress reporting and never cancelled
+    pub fn none() -> SolverMonitor<'static> {
+        SolverMonitor {
+            progress: &(),
+            cancellation: &(),
+        }
+    }
+
+    // Same cancellation without progress reports, for the runs of a search packing the items many times
+    pub fn silent(&self) -> SolverMonitor<'a> {
+        SolverMonitor {
+            progress: &(),
+            cancellation: self.cancellation,
+        }
+    }
+
+    pub fn report(&self, event: ProgressEvent) {
+        self.progress.report(&event);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancellation.is_cancelled()
+    }
+}
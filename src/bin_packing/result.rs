@@ -0,0 +1,360 @@
+use crate::bin_packing::bin::{Bin, DLBinWithPackedOrders};
+use crate::bin_packing::item::Item;
+use crate::bin_packing::options::PlanObjective;
+use crate::bin_packing::order::DimensionLessOrder;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+// Reason why an item could not be placed into a bin
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum UnpackedReason {
+    OverWeight,
+    OverVolume,
+    NoFreePosition,
+    OrientationForbidden,
+    // base of the item would not rest on enough supporting area
+    Unsupported,
+    // an item below would carry more than its maximum top load
+    TopLoadExceeded,
+    // an axle of the bin would be overloaded
+    AxleOverload,
+    // the only bins that take the item cannot be balanced
+    Unbalanced,
+    // the run was cancelled before the item was placed
+    Cancelled,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct UnpackedItem {
+    pub name: String,
+    pub reason: UnpackedReason,
+}
+
+impl UnpackedItem {
+    pub fn new(item: &Item, reason: UnpackedReason) -> UnpackedItem {
+        UnpackedItem {
+            name: item.name.clone(),
+            reason,
+        }
+    }
+}
+
+// Constraint broken by a packing plan, by the plan as a whole or by one of its items
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Violation {
+    CenterOfGravityOutsideEnvelope {
+        center_of_gravity: [f32; 3],
+    },
+    ItemOverlap {
+        item_name: String,
+        other_item_name: String,
+    },
+    // item reaches outside the bin walls
+    OutOfBounds {
+        item_name: String,
+    },
+    OverWeight {
+        weight: f32,
+        max_weight: f32,
+    },
+    // unknown rotation, or a rotation that tips over an item that must stay upright
+    OrientationForbidden {
+        item_name: String,
+        rotation: String,
+    },
+    // less of the base rests on the floor or on items below than the min support ratio
+    Unsupported {
+        item_name: String,
+        support_ratio: f32,
+    },
+    TopLoadExceeded {
+        item_name: String,
+        top_load: f32,
+        max_top_load: f32,
+    },
+    AxleOverload {
+        axle_index: usize,
+        load: f32,
+        max_load: f32,
+    },
+}
+
+/*
+    Result of packing an item list into a single bin
+    If no bin holds all items, bin is the fullest bin achieved and unpacked_items lists what was left out
+*/
+#[derive(Serialize, Clone, Debug)]
+pub struct PackingResult {
+    pub bin: Option<Bin>,
+    pub unpacked_items: Vec<UnpackedItem>,
+    pub violations: Vec<Violation>,
+}
+
+impl PackingResult {
+    pub fn is_complete(&self) -> bool {
+        self.bin.is_some() && self.unpacked_items.is_empty() && self.violations.is_empty()
+    }
+
+    pub fn get_objective_value(&self, objective: PlanObjective) -> f32 {
+        self.bin
+            .as_ref()
+            .map_or(0.0, |bin| get_objective_value(bin, objective))
+    }
+}
+
+/*
+    Floor length taken by a load. Loading meters are the floor length in meters,
+    area loading meters are the floor area of the bottom items spread over the bin width.
+*/
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct LoadingMeters {
+    // in the length unit of the bin
+    pub floor_length: f32,
+    pub loading_meters: f32,
+    pub area_loading_meters: f32,
+}
+
+// Result of planning pallets onto the floor of a truck
+#[derive(Serialize, Clone, Debug)]
+pub struct FloorPlanResult {
+    pub bin: Bin,
+    pub unpacked_items: Vec<UnpackedItem>,
+    pub violations: Vec<Violation>,
+    // area loading meters taken by each order, by order name, empty without units_per_meter
+    pub order_loading_meters: BTreeMap<String, f32>,
+}
+
+// Result of packing an item list into as many bins as needed
+#[derive(Serialize, Clone, Debug)]
+pub struct MultiBinPackingResult {
+    pub bins: Vec<Bin>,
+    pub unpacked_items: Vec<UnpackedItem>,
+}
+
+impl MultiBinPackingResult {
+    pub fn get_objective_value(&self, objective: PlanObjective) -> f32 {
+        match objective {
+            // overall utilisation of the opened bins
+            PlanObjective::Utilisation => {
+                let bin_volume: f32 = self.bins.iter().map(|bin| bin.get_volume()).sum();
+                let packed_volume: f32 = self
+                    .bins
+                    .iter()
+                    .map(|bin| bin.get_packed_items_volume())
+                    .sum();
+                if bin_volume > 0.0 {
+                    packed_volume / bin_volume
+                } else {
+                    0.0
+                }
+            }
+            _ => self
+                .bins
+                .iter()
+                .map(|bin| get_objective_value(bin, objective))
+                .sum(),
+        }
+    }
+}
+
+fn get_objective_value(bin: &Bin, objective: PlanObjective) -> f32 {
+    match objective {
+        PlanObjective::Utilisation => {
+            if bin.get_volume() > 0.0 {
+                bin.get_packed_items_volume() / bin.get_volume()
+            } else {
+                0.0
+            }
+        }
+        PlanObjective::PackedVolume => bin.get_packed_items_volume(),
+        PlanObjective::PackedWeight => bin.get_packed_items_weight(),
+        PlanObjective::PackedCount => bin.packed_items.len() as f32,
+    }
+}
+
+// Reason why an additional dimensionless order was not assigned to any bin
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum UnassignedReason {
+    // order exceeds the remaining capacity of every bin
+    ExceedsCapacity,
+    // order fits a bin, but other orders gave a better load
+    NotSelected,
+    // bins limit volume, the order has none and the missing volume policy gives no estimate
+    MissingVolume,
+    // the other orders of its group cannot be loaded with it
+    GroupIncomplete,
+    // order conflicts with the load of every bin it fits
+    Conflict,
+    // order fits a bin type, but every bin of the types it fits is already opened
+    BinsExhausted,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct UnassignedOrder {
+    pub id: String,
+    pub reason: UnassignedReason,
+}
+
+impl UnassignedOrder {
+    pub fn new(order: &DimensionLessOrder, reason: UnassignedReason) -> UnassignedOrder {
+        UnassignedOrder {
+            id: order.id.clone(),
+            reason,
+        }
+    }
+}
+
+// Reason why the must include orders of a bin cannot be loaded
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum InfeasibleReason {
+    // order is not among the additional orders, or another bin must include it
+    NotAvailable { order_id: String },
+    // bins limit volume and the order, or another order of its group, has none
+    MissingVolume { order_id: String },
+    // orders of the group conflict with each other or with the load of the bin
+    Conflict { order_id: String },
+    // must include orders together exceed the remaining capacity
+    ExceedsCapacity,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct InfeasibleBin {
+    pub id: String,
+    pub reason: InfeasibleReason,
+}
+
+impl InfeasibleBin {
+    pub fn new(bin: &DLBinWithPackedOrders, reason: InfeasibleReason) -> InfeasibleBin {
+        InfeasibleBin {
+            id: bin.id.clone(),
+            reason,
+        }
+    }
+}
+
+// Objective value reached for a bin and how far it may be from the best possible value
+#[derive(Serialize, Clone, Debug)]
+pub struct BinOptimality {
+    pub id: String,
+    // selection of the exact knapsack, or in exclusive mode part of the best assignment,
+    // upper_bound is then the value itself
+    pub exact: bool,
+    // objective value of the additional orders of the bin
+    pub value: f64,
+    // no selection of the additional orders reaches more than this value
+    pub upper_bound: f64,
+    // (upper_bound - value) / upper_bound, 0.0 for an optimal selection
+    pub gap: f64,
+}
+
+impl BinOptimality {
+    pub fn new(
+        bin: &DLBinWithPackedOrders,
+        exact: bool,
+        value: f64,
+        upper_bound: f64,
+    ) -> BinOptimality {
+        let gap = if upper_bound > 0.0 {
+            (upper_bound - value) / upper_bound
+        } else {
+            0.0
+        };
+        BinOptimality {
+            id: bin.id.clone(),
+            exact,
+            value,
+            upper_bound,
+            gap,
+        }
+    }
+}
+
+// Objective value of all bins together, in exclusive mode where the bins compete for the same orders
+#[derive(Serialize, Clone, Debug)]
+pub struct AssignmentOptimality {
+    // assignment proven best over all bins, upper_bound is then the value itself
+    pub exact: bool,
+    // objective value of the additional orders of all bins
+    pub value: f64,
+    // no assignment of the additional orders reaches more than this value
+    pub upper_bound: f64,
+    // (upper_bound - value) / upper_bound, 0.0 for an optimal assignment
+    pub gap: f64,
+}
+
+impl AssignmentOptimality {
+    pub fn new(exact: bool, value: f64, upper_bound: f64) -> AssignmentOptimality {
+        let gap = if upper_bound > 0.0 {
+            (upper_bound - value) / upper_bound
+        } else {
+            0.0
+        };
+        AssignmentOptimality {
+            exact,
+            value,
+            upper_bound,
+            gap,
+        }
+    }
+}
+
+// Result of packing additional dimensionless orders into bins
+#[derive(Serialize)]
+pub struct DLPackingResult {
+    // bins that received additional orders
+    pub bins: Vec<DLBinWithPackedOrders>,
+    pub unassigned_orders: Vec<UnassignedOrder>,
+    // bins whose must include orders cannot be loaded, they receive no additional orders
+    pub infeasible_bins: Vec<InfeasibleBin>,
+    // one entry for every bin that was solved, including bins that received no orders.
+    // In exclusive mode a bin is bounded by what it could get on its own
+    pub optimality: Vec<BinOptimality>,
+    // exclusive mode only, all bins together
+    pub assignment_optimality: Option<AssignmentOptimality>,
+}
+
+// Selection of additional orders offered as an alternative for one bin
+#[derive(Serialize, Clone)]
+pub struct DLAlternative {
+    // additional orders, including the orders the bin must include
+    pub orders: Vec<DimensionLessOrder>,
+    // objective value of the additional orders
+    pub value: f64,
+    // share (0.0 - 1.0) of max_weight used by the packed and the additional orders, 0.0 if max_weight is 0
+    pub weight_utilisation: f64,
+    // share of max_volume, None if the bin has no volume limit, 0.0 if it is 0
+    pub volume_utilisation: Option<f64>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct DLBinAlternatives {
+    pub id: String,
+    pub alternatives: Vec<DLAlternative>,
+    // false if the search hit the state limit and may miss alternatives
+    pub complete: bool,
+}
+
+// Alternative selections of additional orders for every bin
+#[derive(Serialize)]
+pub struct DLAlternativesResult {
+    pub bins: Vec<DLBinAlternatives>,
+    // bins whose must include orders cannot be loaded
+    pub infeasible_bins: Vec<InfeasibleBin>,
+}
+
+// Bins opened for a list of dimensionless orders
+#[derive(Serialize)]
+pub struct DLBinPackingResult {
+    // opened bins with their orders in additional_packed_orders, copies of a bin type keep its id
+    pub bins: Vec<DLBinWithPackedOrders>,
+    pub total_cost: f64,
+    // no assignment of the packed orders opens bins for less than this cost
+    pub lower_bound: f64,
+    // (total_cost - lower_bound) / total_cost
+    pub gap: f64,
+    pub unassigned_orders: Vec<UnassignedOrder>,
+}
@@ -0,0 +1,226 @@
+/*
+    Approximate knapsack for inputs too large for the exact knapsack.
+    Resources are weighed against each other by one multiplier per resource (Lagrangian relaxation).
+    For given multipliers, an item is worth taking if its value is above its weighed usage,
+    and the multipliers plus the value above weighed usage of these items bound the best value from above.
+    The search starts from the best bound of a single resource (the Dantzig bound of its fractional knapsack)
+    and a subgradient search lowers the bound further, after every step the items are added greedily
+    by value per weighed usage and the best selection found is kept.
+    The bound ignores conflicts, so the reported gap may be larger than the real one, never smaller.
+*/
+use crate::bin_packing::options::DimensionLessObjective;
+use crate::bin_packing::order::DimensionLessOrder;
+use crate::bin_packing::solver::knapsack::{intersects, union_into, KnapsackItem};
+
+// Selection of the approximate knapsack with the bound on the best possible value
+pub struct ApproximateSelection {
+    // indices of the selected items
+    pub items: Vec<usize>,
+    pub orders: Vec<DimensionLessOrder>,
+    pub upper_bound: f64,
+    // multiplier per unit of every resource giving the bound, 0.0 for resources without capacity
+    pub multipliers: Vec<f64>,
+}
+
+// Usage of every item as share of the capacity, resources without capacity count as unused
+fn get_relative_usages(items: &[KnapsackItem], capacities: &[f64]) -> Vec<Vec<f64>> {
+    items
+        .iter()
+        .map(|item| {
+            item.usage
+                .iter()
+                .zip(capacities)
+                .map(|(&usage, &capacity)| match capacity > 0.0 {
+                    true => usage / capacity,
+                    false => 0.0,
+                })
+                .collect()
+        })
+        .collect()
+}
+
+// Adds the items by value per weighed usage while they fit and do not conflict, returns the item indices
+fn fill_greedy(
+    items: &[KnapsackItem],
+    values: &[f64],
+    relative_usages: &[Vec<f64>],
+    capacities: &[f64],
+    initial_classes: &[u64],
+    multipliers: &[f64],
+) -> Vec<usize> {
+    let score = |i: usize| {
+        let weighed_usage: f64 = relative_usages[i]
+            .iter()
+            .zip(multipliers)
+            .map(|(usage, multiplier)| usage * multiplier)
+            .sum();
+        match weighed_usage > 0.0 {
+            true => values[i] / weighed_usage,
+            false => f64::INFINITY,
+        }
+    };
+    let mut order: Vec<(f64, usize)> = (0..items.len())
+        .filter(|&i| values[i] > 0.0)
+        .map(|i| (score(i), i))
+        .collect();
+    order.sort_by(|(score_a, a), (score_b, b)| {
+        score_b
+            .total_cmp(score_a)
+            .then(values[*b].total_cmp(&values[*a]))
+    });
+
+    let mut used = vec![0.0; capacities.len()];
+    let mut classes = initial_classes.to_vec();
+    let mut selected = Vec::new();
+    for (_, i) in order {
+        let item = &items[i];
+        let fits = used
+            .iter()
+            .zip(&item.usage)
+            .zip(capacities)
+            .all(|((used, usage), capacity)| used + usage <= *capacity);
+        if fits && !intersects(&classes, &item.conflicts) {
+            for (used, usage) in used.iter_mut().zip(&item.usage) {
+                *used += usage;
+            }
+            union_into(&mut classes, &item.classes);
+            selected.push(i);
+        }
+    }
+    selected
+}
+
+/*
+    Bound of the fractional knapsack over one resource: items by value per usage, the last one split.
+    Returns the bound and the value per usage of the split item, the multiplier giving the same Lagrangian bound.
+*/
+fn get_dantzig_bound(values: &[f64], relative_usages: &[Vec<f64>], resource: usize) -> (f64, f64) {
+    // items without usage come first, their density is infinite
+    let mut by_density: Vec<(f64, f64, f64)> = values
+        .iter()
+        .zip(relative_usages)
+        .filter(|(&value, _)| value > 0.0)
+        .map(|(&value, usage)| match usage[resource] > 0.0 {
+            true => (value / usage[resource], value, usage[resource]),
+            false => (f64::INFINITY, value, 0.0),
+        })
+        .collect();
+    by_density.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+    let mut bound = 0.0;
+    let mut remaining_capacity = 1.0;
+    for (density, value, usage) in by_density {
+        if usage <= remaining_capacity {
+            bound += value;
+            remaining_capacity -= usage;
+        } else {
+            return (bound + density * remaining_capacity, density);
+        }
+    }
+    (bound, 0.0)
+}
+
+pub fn knapsack_nd_approximate(
+    items: &[KnapsackItem],
+    capacities: &[f64],
+    initial_classes: &[u64],
+    objective: DimensionLessObjective,
+    iterations: usize,
+) -> ApproximateSelection {
+    let values: Vec<f64> = items
+        .iter()
+        .map(|item| item.get_objective_value(objective))
+        .collect();
+    let relative_usages = get_relative_usages(items, capacities);
+
+    // the tightest single resource bound, its multiplier alone gives the same Lagrangian bound
+    let mut multipliers = vec![0.0; capacities.len()];
+    let mut upper_bound: f64 = values.iter().filter(|&&value| value > 0.0).sum();
+    for resource in 0..capacities.len() {
+        let (bound, multiplier) = get_dantzig_bound(&values, &relative_usages, resource);
+        if bound < upper_bound {
+            upper_bound = bound;
+            multipliers.iter_mut().for_each(|m| *m = 0.0);
+            multipliers[resource] = multiplier;
+        }
+    }
+    let mut best_multipliers = multipliers.clone();
+    let mut best_selection = Vec::new();
+    let mut best_value = 0.0;
+    // step size factor, halved when the bound stops improving
+    let mut step_factor = 1.0;
+    let mut steps_without_improvement = 0;
+
+    for _ in 0..iterations.max(1) {
+        let selection = fill_greedy(
+            items,
+            &values,
+            &relative_usages,
+            capacities,
+            initial_classes,
+            &multipliers,
+        );
+        let value: f64 = selection.iter().map(|&i| values[i]).sum();
+        if value > best_value {
+            best_value = value;
+            best_selection = selection;
+        }
+
+        // bound of the current multipliers, the subgradient is the capacity left by the items worth taking
+        let mut bound: f64 = multipliers.iter().sum();
+        let mut subgradient = vec![1.0; capacities.len()];
+        for (value, usage) in values.iter().zip(&relative_usages) {
+            let weighed_usage: f64 = usage
+                .iter()
+                .zip(&multipliers)
+                .map(|(usage, multiplier)| usage * multiplier)
+                .sum();
+            if value > &weighed_usage {
+                bound += value - weighed_usage;
+                for (gradient, usage) in subgradient.iter_mut().zip(usage) {
+                    *gradient -= usage;
+                }
+            }
+        }
+        if bound < upper_bound {
+            upper_bound = bound;
+            best_multipliers.clone_from(&multipliers);
+            steps_without_improvement = 0;
+        } else {
+            steps_without_improvement += 1;
+            if steps_without_improvement >= 5 {
+                step_factor /= 2.0;
+                steps_without_improvement = 0;
+            }
+        }
+
+        let squared_norm: f64 = subgradient.iter().map(|gradient| gradient * gradient).sum();
+        let gap = bound - best_value;
+        if squared_norm <= 0.0 || gap <= 0.0 {
+            break;
+        }
+        let step = step_factor * gap / squared_norm;
+        for (multiplier, gradient) in multipliers.iter_mut().zip(&subgradient) {
+            *multiplier = (*multiplier - step * gradient).max(0.0);
+        }
+    }
+
+    best_selection.sort_unstable();
+    ApproximateSelection {
+        orders: best_selection
+            .iter()
+            .flat_map(|&i| items[i].orders.clone())
+            .collect(),
+        items: best_selection,
+        upper_bound: upper_bound.max(best_value),
+        // the multipliers weigh the usage relative to the capacity
+        multipliers: best_multipliers
+            .iter()
+            .zip(capacities)
+            .map(|(multiplier, &capacity)| match capacity > 0.0 {
+                true => multiplier / capacity,
+                false => 0.0,
+            })
+            .collect(),
+    }
+}
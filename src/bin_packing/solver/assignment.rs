@@ -0,0 +1,173 @@
+/*
+    Assignment of order groups to several bins at once, every group to at most one bin (multiple knapsack).
+    A branch and bound search tries every bin for every group, starting from a known assignment.
+    Its bound relaxes all bins into one knapsack over weight, filled fractionally by value per weight.
+    The number of search nodes is limited, above the limit the best assignment found is returned.
+*/
+use crate::bin_packing::solver::knapsack::{intersects, union_into, ClassSet, KnapsackItem};
+
+// Bin of the assignment, with the groups it may take
+pub struct AssignmentBin<'a> {
+    // remaining capacities, weight first
+    pub capacities: Vec<f64>,
+    // classes already in the bin
+    pub classes: ClassSet,
+    // knapsack item of every group, None if the bin can not take the group
+    pub items: Vec<Option<&'a KnapsackItem>>,
+}
+
+// Bin of every group, None for groups left unassigned
+pub struct Assignment {
+    pub bins: Vec<Option<usize>>,
+    pub value: f64,
+    // false if the node limit stopped the search, a better assignment may then exist
+    pub complete: bool,
+}
+
+struct Search<'a, 'b> {
+    bins: &'b [AssignmentBin<'a>],
+    values: &'b [f64],
+    weights: &'b [f64],
+    // groups by value per weight, the order they are branched on
+    group_order: Vec<usize>,
+    used: Vec<Vec<f64>>,
+    classes: Vec<ClassSet>,
+    current: Vec<Option<usize>>,
+    value: f64,
+    best: Assignment,
+    nodes: usize,
+    max_nodes: usize,
+}
+
+impl Search<'_, '_> {
+    // Most value the groups from the position on add, packed fractionally into the weight left in all bins
+    fn get_bound(&self, position: usize) -> f64 {
+        let mut remaining_weight: f64 = self
+            .bins
+            .iter()
+            .zip(&self.used)
+            .map(|(bin, used)| bin.capacities[0] - used[0])
+            .sum();
+        let mut bound = 0.0;
+        for &group in &self.group_order[position..] {
+            let value = self.values[group];
+            if value <= 0.0 {
+                break;
+            }
+            if self.weights[group] <= remaining_weight {
+                bound += value;
+                remaining_weight -= self.weights[group];
+            } else {
+                bound += value * remaining_weight / self.weights[group];
+                break;
+            }
+        }
+        bound
+    }
+
+    fn fits(&self, bin: usize, item: &KnapsackItem) -> bool {
+        let capacities = &self.bins[bin].capacities;
+        (0..capacities.len()).all(|d| self.used[bin][d] + item.usage[d] <= capacities[d])
+            && !intersects(&self.classes[bin], &item.conflicts)
+    }
+
+    fn branch(&mut self, position: usize) {
+        self.nodes += 1;
+        if self.nodes > self.max_nodes {
+            self.best.complete = false;
+            return;
+        }
+        if self.value > self.best.value {
+            self.best.value = self.value;
+            self.best.bins.clone_from(&self.current);
+        }
+        if position == self.group_order.len() {
+            return;
+        }
+        // allowance for the rounding of the summed values
+        let threshold = self.best.value + 1e-9 * self.best.value.abs();
+        if self.value + self.get_bound(position) <= threshold {
+            return;
+        }
+
+        let group = self.group_order[position];
+        for bin in 0..self.bins.len() {
+            let Some(item) = self.bins[bin].items[group] else {
+                continue;
+            };
+            if !self.fits(bin, item) {
+                continue;
+            }
+            let classes = self.classes[bin].clone();
+            for (used, usage) in self.used[bin].iter_mut().zip(&item.usage) {
+                *used += usage;
+            }
+            union_into(&mut self.classes[bin], &item.classes);
+            self.current[group] = Some(bin);
+            self.value += self.values[group];
+
+            self.branch(position + 1);
+
+            self.value -= self.values[group];
+            self.current[group] = None;
+            self.classes[bin] = classes;
+            for (used, usage) in self.used[bin].iter_mut().zip(&item.usage) {
+                *used -= usage;
+            }
+        }
+        self.branch(position + 1);
+    }
+}
+
+/*
+    Best assignment of the groups to the bins by the sum of the group values.
+    values and weights are given per group, initial is an assignment that fits, e.g. from filling bins one by one.
+*/
+pub fn assign_groups_to_bins(
+    bins: &[AssignmentBin],
+    values: &[f64],
+    weights: &[f64],
+    initial: Vec<Option<usize>>,
+    max_nodes: usize,
+) -> Assignment {
+    let density = |group: usize| match weights[group] > 0.0 {
+        true => values[group] / weights[group],
+        false => f64::INFINITY,
+    };
+    // groups without value are never worth branching on first, they come last
+    let mut group_order: Vec<usize> = (0..values.len()).collect();
+    group_order.sort_by(|&a, &b| {
+        (values[b] > 0.0)
+            .cmp(&(values[a] > 0.0))
+            .then(density(b).total_cmp(&density(a)))
+    });
+
+    let initial_value = initial
+        .iter()
+        .zip(values)
+        .filter(|(bin, _)| bin.is_some())
+        .map(|(_, value)| value)
+        .sum();
+    let mut search = Search {
+        bins,
+        values,
+        weights,
+        group_order,
+        used: bins
+            .iter()
+            .map(|bin| vec![0.0; bin.capacities.len()])
+            .collect(),
+        classes: bins.iter().map(|bin| bin.classes.clone()).collect(),
+        current: vec![None; values.len()],
+        value: 0.0,
+        best: Assignment {
+            bins: initial,
+            value: initial_value,
+            complete: true,
+        },
+        nodes: 0,
+        max_nodes,
+    };
+    search.branch(0);
+    search.best
+}
@@ -0,0 +1,240 @@
+use crate::bin_packing::bin::{Axle, Bin};
+use crate::bin_packing::result::Violation;
+
+/*
+    Plan level checks on a packed bin.
+    These depend on the whole load, so they are checked once the bin is filled instead of per item.
+*/
+pub fn get_plan_violations(bin: &Bin) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    if !bin.is_balanced() {
+        if let Some(center_of_gravity) = bin.get_center_of_gravity() {
+            violations.push(Violation::CenterOfGravityOutsideEnvelope { center_of_gravity });
+        }
+    }
+    violations
+}
+
+/*
+    Share of a load carried by each axle, for a load centred at the given depth.
+    Between two axles the load is split by the lever rule. In front of the first or behind the last axle
+    the two nearest axles are used, so the further one may be lifted (negative share).
+    A single axle carries the whole load.
+*/
+pub fn get_axle_shares(axles: &[Axle], load_position: f32, load: f32) -> Vec<f32> {
+    let mut shares = vec![0.0; axles.len()];
+    match axles.len() {
+        0 => {}
+        1 => shares[0] = load,
+        _ => {
+            // index of the first axle of the pair that carries the load
+            let front = axles
+                .windows(2)
+                .position(|pair| load_position <= pair[1].position)
+                .unwrap_or(axles.len() - 2);
+            let span = axles[front + 1].position - axles[front].position;
+            if span <= 0.0 {
+                // axles at the same position share the load evenly
+                shares[front] = load / 2.0;
+                shares[front + 1] = load / 2.0;
+            } else {
+                let rear_share = load * (load_position - axles[front].position) / span;
+                shares[front] = load - rear_share;
+                shares[front + 1] = rear_share;
+            }
+        }
+    }
+    shares
+}
+
+// Axle loads of the bin after adding an item of the given depth extent
+pub fn get_axle_loads_with_item(
+    bin: &Bin,
+    item_dimension: &[f32; 3],
+    item_position: &[f32; 3],
+    item_weight: f32,
+) -> Vec<f32> {
+    let load_position = item_position[2] + item_dimension[2] / 2.0;
+    get_axle_shares(&bin.axles, load_position, item_weight)
+        .iter()
+        .zip(&bin.axle_loads)
+        .map(|(share, load)| load + share)
+        .collect()
+}
+
+pub fn check_axle_loads(bin: &Bin, axle_loads: &[f32]) -> bool {
+    bin.axles
+        .iter()
+        .zip(axle_loads)
+        .all(|(axle, &load)| load <= axle.max_load)
+}
+
+// Axles carrying more than their max load, tolerance allows for rounding of loads summed up elsewhere
+pub fn get_axle_violations(bin: &Bin, tolerance: f32) -> Vec<Violation> {
+    bin.axles
+        .iter()
+        .zip(&bin.axle_loads)
+        .enumerate()
+        .filter(|(_, (axle, &load))| load > axle.max_load + tolerance)
+        .map(|(axle_index, (axle, &load))| Violation::AxleOverload {
+            axle_index,
+            load,
+            max_load: axle.max_load,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bin_packing::bin::{CogEnvelope, RawBin};
+    use crate::bin_packing::item::{Item, RawItem};
+    use crate::bin_packing::options::PackingOptions;
+    use crate::bin_packing::order::RawOrder;
+    use crate::bin_packing::progress::SolverMonitor;
+    use crate::bin_packing::result::UnpackedReason;
+    use crate::bin_packing::solver::{
+        get_smallest_fitting_bin_for_item_vector, pack_items_into_bin,
+    };
+
+    fn get_bin(cog_envelope: Option<CogEnvelope>, axles: Option<Vec<Axle>>) -> Bin {
+        RawBin {
+            name: "truck".to_string(),
+            width: 100.0,
+            height: 100.0,
+            depth: 100.0,
+            max_weight: 1000.0,
+            cog_envelope,
+            axles,
+        }
+        .convert_to_bin()
+    }
+
+    // Slice of the full bin width and height, 10 deep
+    fn get_slice(name: &str, weight: f32) -> Item {
+        let mut items = RawOrder {
+            name: name.to_string(),
+            items: vec![RawItem {
+                name: "slice".to_string(),
+                quantity: 1,
+                width: 100.0,
+                height: 100.0,
+                depth: 10.0,
+                weight,
+                max_top_load: None,
+            }],
+            orientable: Some(false),
+            stackable: None,
+        }
+        .create_order_from_raw_order()
+        .items;
+        items.remove(0)
+    }
+
+    fn place_slice(bin: &mut Bin, name: &str, weight: f32, depth_position: f32) {
+        let position = [0.0, 0.0, depth_position];
+        bin.packed_items.push(Item {
+            position,
+            ..get_slice(name, weight)
+        });
+    }
+
+    #[test]
+    fn center_of_gravity_is_the_weighted_mean_of_the_item_centres() {
+        let mut bin = get_bin(None, None);
+        assert_eq!(bin.get_center_of_gravity(), None);
+        place_slice(&mut bin, "front", 1.0, 0.0);
+        place_slice(&mut bin, "rear", 3.0, 90.0);
+        assert_eq!(bin.get_center_of_gravity(), Some([50.0, 50.0, 72.5]));
+    }
+
+    #[test]
+    fn center_of_gravity_outside_the_envelope_is_a_violation() {
+        let envelope = CogEnvelope {
+            width: None,
+            height: None,
+            depth: Some(0.1),
+        };
+        let mut bin = get_bin(Some(envelope), None);
+        place_slice(&mut bin, "front", 1.0, 0.0);
+        place_slice(&mut bin, "rear", 1.0, 90.0);
+        assert!(bin.is_balanced());
+        assert!(get_plan_violations(&bin).is_empty());
+
+        place_slice(&mut bin, "rear", 2.0, 80.0);
+        assert!(!bin.is_balanced());
+        assert!(matches!(
+            get_plan_violations(&bin)[..],
+            [Violation::CenterOfGravityOutsideEnvelope { .. }]
+        ));
+    }
+
+    #[test]
+    fn plan_with_the_load_at_the_front_is_not_complete() {
+        let envelope = CogEnvelope {
+            width: None,
+            height: None,
+            depth: Some(0.1),
+        };
+        let result = get_smallest_fitting_bin_for_item_vector(
+            &[get_slice("front", 10.0)],
+            &mut [get_bin(Some(envelope), None)],
+            10.0,
+            100_000.0,
+            &PackingOptions::default(),
+            &SolverMonitor::none(),
+        );
+        assert!(!result.is_complete());
+        assert_eq!(result.violations.len(), 1);
+    }
+
+    #[test]
+    fn axles_share_a_load_by_the_lever_rule() {
+        let axles = [
+            Axle {
+                position: 0.0,
+                max_load: 100.0,
+            },
+            Axle {
+                position: 100.0,
+                max_load: 100.0,
+            },
+        ];
+        assert_eq!(get_axle_shares(&axles, 25.0, 40.0), [30.0, 10.0]);
+        // behind the rear axle the front axle is lifted
+        assert_eq!(get_axle_shares(&axles, 150.0, 40.0), [-20.0, 60.0]);
+        assert_eq!(get_axle_shares(&axles[..1], 150.0, 40.0), [40.0]);
+    }
+
+    #[test]
+    fn items_that_overload_an_axle_are_not_packed() {
+        // the bin takes every slice by weight, the front axle can not carry its share of all of them
+        let axles = vec![
+            Axle {
+                position: 0.0,
+                max_load: 100.0,
+            },
+            Axle {
+                position: 100.0,
+                max_load: 1000.0,
+            },
+        ];
+        let mut bin = get_bin(None, Some(axles));
+        let slices: Vec<Item> = (0..10)
+            .map(|i| get_slice(&format!("slice{i}"), 40.0))
+            .collect();
+        let unpacked = pack_items_into_bin(
+            &mut bin,
+            &slices,
+            &PackingOptions::default(),
+            0,
+            &SolverMonitor::none(),
+        );
+        assert!(!unpacked.is_empty());
+        assert!(unpacked
+            .iter()
+            .all(|(_, reason)| *reason == UnpackedReason::AxleOverload));
+        assert!(bin.axle_loads[0] <= 100.0);
+        assert!(get_axle_violations(&bin, 0.0).is_empty());
+    }
+}
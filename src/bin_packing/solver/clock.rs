@@ -0,0 +1,40 @@
+/*
+    Wall clock for time limits of the solver.
+    std::time::Instant is not available in the browser, there the javascript clock is used.
+*/
+
+#[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+pub struct Stopwatch {
+    started_at: f64,
+}
+
+#[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+impl Stopwatch {
+    pub fn start() -> Stopwatch {
+        Stopwatch {
+            started_at: js_sys::Date::now(),
+        }
+    }
+
+    pub fn get_elapsed_ms(&self) -> f64 {
+        js_sys::Date::now() - self.started_at
+    }
+}
+
+#[cfg(not(all(feature = "wasm", target_arch = "wasm32")))]
+pub struct Stopwatch {
+    started_at: std::time::Instant,
+}
+
+#[cfg(not(all(feature = "wasm", target_arch = "wasm32")))]
+impl Stopwatch {
+    pub fn start() -> Stopwatch {
+        Stopwatch {
+            started_at: std::time::Instant::now(),
+        }
+    }
+
+    pub fn get_elapsed_ms(&self) -> f64 {
+        self.started_at.elapsed().as_secs_f64() * 1000.0
+    }
+}
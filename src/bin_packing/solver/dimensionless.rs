@@ -0,0 +1,1531 @@
+/*
+    Packs additional DIMENSION-LESS orders into DIMENSION-LESS bins that may already have packed orders.
+    Only weight, volume and named resources (pallet spaces, loading meters, ...) are considered,
+    together with the loading rules of solver::rules.
+*/
+use crate::bin_packing::bin::DLBinWithPackedOrders;
+use crate::bin_packing::options::{
+    AlternativesMode, DimensionLessObjective, DimensionLessOptions, MissingVolumePolicy,
+    ObjectiveFactors, SolveMode,
+};
+use crate::bin_packing::order::DimensionLessOrder;
+use crate::bin_packing::result::{
+    AssignmentOptimality, BinOptimality, DLAlternative, DLAlternativesResult, DLBinAlternatives,
+    DLBinPackingResult, DLPackingResult, InfeasibleBin, InfeasibleReason, UnassignedOrder,
+    UnassignedReason,
+};
+use crate::bin_packing::solver::approximate::knapsack_nd_approximate;
+use crate::bin_packing::solver::assignment::{assign_groups_to_bins, AssignmentBin};
+use crate::bin_packing::solver::knapsack::{
+    intersects, knapsack_nd_float, knapsack_nd_pareto, knapsack_nd_top_k, union_into, ClassSet,
+    KnapsackItem, KnapsackSelections, StateLimit,
+};
+use crate::bin_packing::solver::rules::{get_order_groups, ConflictRules};
+use std::collections::BTreeMap;
+
+// Volume of an order in a bin, None if it is missing and the policy gives no estimate
+fn get_order_volume(
+    bin: &DLBinWithPackedOrders,
+    order: &DimensionLessOrder,
+    policy: MissingVolumePolicy,
+) -> Option<f64> {
+    match (order.volume, policy) {
+        (Some(volume), _) => Some(volume),
+        (None, MissingVolumePolicy::Reject) => None,
+        (None, MissingVolumePolicy::Zero) => Some(0.0),
+        (None, MissingVolumePolicy::Density) => bin
+            .density
+            .filter(|&density| density > 0.0)
+            .map(|density| order.weight / density),
+    }
+}
+
+/*
+    Resources used by an order in a bin: weight, volume if the bin limits it, then the named resources of the bin.
+    None if the bin limits volume and the volume of the order is unknown.
+*/
+fn get_order_usage(
+    bin: &DLBinWithPackedOrders,
+    order: &DimensionLessOrder,
+    policy: MissingVolumePolicy,
+) -> Option<Vec<f64>> {
+    let mut usage = vec![order.weight];
+    if bin.max_volume.is_some() {
+        usage.push(get_order_volume(bin, order, policy)?);
+    }
+    for name in bin.max_resources.keys() {
+        // orders that do not mention a resource do not use it
+        usage.push(order.resources.get(name).copied().unwrap_or(0.0));
+    }
+    Some(usage)
+}
+
+/*
+    Remaining capacity of a bin after its packed orders, in the same order as get_order_usage.
+    Packed orders are already loaded, an unknown volume is counted as zero for them.
+*/
+fn get_remaining_capacities(bin: &DLBinWithPackedOrders, policy: MissingVolumePolicy) -> Vec<f64> {
+    let mut capacities = vec![bin.max_weight];
+    if let Some(max_vol) = bin.max_volume {
+        capacities.push(max_vol);
+    }
+    capacities.extend(bin.max_resources.values());
+
+    for packed_order in &bin.packed_orders {
+        let usage = get_order_usage(bin, packed_order, policy)
+            .or_else(|| get_order_usage(bin, packed_order, MissingVolumePolicy::Zero))
+            .unwrap_or_default();
+        for (capacity, used) in capacities.iter_mut().zip(usage) {
+            *capacity -= used;
+        }
+    }
+    capacities
+}
+
+// Check if the order alone fits into the remaining capacity of the bin
+fn fits_remaining_capacity(
+    bin: &DLBinWithPackedOrders,
+    order: &DimensionLessOrder,
+    policy: MissingVolumePolicy,
+) -> bool {
+    // orders with an unknown volume are not offered to bins with a volume limit
+    let Some(usage) = get_order_usage(bin, order, policy) else {
+        return false;
+    };
+    usage
+        .iter()
+        .zip(get_remaining_capacities(bin, policy))
+        .all(|(&used, capacity)| used <= capacity)
+}
+
+// Orders of a group offered to a bin as one knapsack item
+fn get_knapsack_item(
+    bin: &DLBinWithPackedOrders,
+    group: &[&DimensionLessOrder],
+    policy: MissingVolumePolicy,
+    rules: &ConflictRules,
+) -> Result<KnapsackItem, UnassignedReason> {
+    let mut usage = get_remaining_capacities(bin, policy);
+    usage.iter_mut().for_each(|used| *used = 0.0);
+    for order in group {
+        let order_usage =
+            get_order_usage(bin, order, policy).ok_or(UnassignedReason::MissingVolume)?;
+        for (used, order_used) in usage.iter_mut().zip(order_usage) {
+            *used += order_used;
+        }
+    }
+    let classes = rules
+        .get_combined_classes(group)
+        .ok_or(UnassignedReason::Conflict)?;
+    Ok(KnapsackItem {
+        orders: group.iter().map(|&o| o.clone()).collect(),
+        usage,
+        conflicts: rules.get_conflicts(&classes),
+        classes,
+    })
+}
+
+fn fits_capacities(usage: &[f64], capacities: &[f64]) -> bool {
+    usage
+        .iter()
+        .zip(capacities)
+        .all(|(used, capacity)| used <= capacity)
+}
+
+// Classes of the orders already packed into the bin
+fn get_packed_classes(bin: &DLBinWithPackedOrders, rules: &ConflictRules) -> ClassSet {
+    let mut classes = rules.get_empty_classes();
+    for order in &bin.packed_orders {
+        union_into(&mut classes, &rules.get_classes(order));
+    }
+    classes
+}
+
+// Orders that must go into the bin, with the other orders of their groups
+fn get_forced_groups<'a>(
+    bin: &DLBinWithPackedOrders,
+    groups: &[Vec<&'a DimensionLessOrder>],
+) -> Vec<Vec<&'a DimensionLessOrder>> {
+    groups
+        .iter()
+        .filter(|group| group.iter().any(|o| bin.must_include.contains(&o.id)))
+        .cloned()
+        .collect()
+}
+
+// Knapsack input for one bin after the orders it must include are loaded
+struct BinSelection {
+    forced_orders: Vec<DimensionLessOrder>,
+    candidate_items: Vec<KnapsackItem>,
+    capacities: Vec<f64>,
+    classes: ClassSet,
+}
+
+/*
+    Orders the bin must include are loaded first, the other groups become knapsack items
+    for the remaining capacity.
+*/
+fn get_bin_selection(
+    bin: &DLBinWithPackedOrders,
+    additional_orders: &[DimensionLessOrder],
+    options: &DimensionLessOptions,
+    rules: &ConflictRules,
+) -> Result<BinSelection, InfeasibleReason> {
+    let policy = options.missing_volume;
+    let groups = get_order_groups(additional_orders);
+
+    if let Some(order_id) = bin
+        .must_include
+        .iter()
+        .find(|id| !additional_orders.iter().any(|o| &o.id == *id))
+    {
+        return Err(InfeasibleReason::NotAvailable {
+            order_id: order_id.clone(),
+        });
+    }
+
+    let mut capacities = get_remaining_capacities(bin, policy);
+    let mut classes = get_packed_classes(bin, rules);
+    let mut forced_orders = Vec::new();
+    for group in &get_forced_groups(bin, &groups) {
+        let order_id = group[0].id.clone();
+        let item = match get_knapsack_item(bin, group, policy, rules) {
+            Ok(item) => item,
+            Err(UnassignedReason::MissingVolume) => {
+                return Err(InfeasibleReason::MissingVolume { order_id })
+            }
+            Err(_) => return Err(InfeasibleReason::Conflict { order_id }),
+        };
+        if intersects(&classes, &item.conflicts) {
+            return Err(InfeasibleReason::Conflict { order_id });
+        }
+        for (capacity, used) in capacities.iter_mut().zip(&item.usage) {
+            *capacity -= used;
+        }
+        union_into(&mut classes, &item.classes);
+        forced_orders.extend(item.orders);
+    }
+    if capacities.iter().any(|&capacity| capacity < 0.0) {
+        return Err(InfeasibleReason::ExceedsCapacity);
+    }
+
+    // Offer the groups that fit the remaining capacity and do not conflict with the load
+    let candidate_items: Vec<KnapsackItem> = groups
+        .iter()
+        .filter(|group| !group.iter().any(|o| bin.must_include.contains(&o.id)))
+        .filter_map(|group| get_knapsack_item(bin, group, policy, rules).ok())
+        .filter(|item| {
+            fits_capacities(&item.usage, &capacities) && !intersects(&classes, &item.conflicts)
+        })
+        .collect();
+
+    Ok(BinSelection {
+        forced_orders,
+        candidate_items,
+        capacities,
+        classes,
+    })
+}
+
+fn get_orders_value(orders: &[DimensionLessOrder], objective: DimensionLessObjective) -> f64 {
+    orders
+        .iter()
+        .map(|o| o.get_objective_value(objective))
+        .sum()
+}
+
+// Auto gives up on the exact knapsack at its limit, Exact goes on with the most valuable states
+fn get_exact_state_limit(options: &DimensionLessOptions) -> StateLimit {
+    let approximation = &options.approximation;
+    match approximation.mode {
+        SolveMode::Auto => StateLimit::Stop(approximation.max_exact_states),
+        SolveMode::Exact => StateLimit::KeepBest(approximation.max_states),
+        SolveMode::Approximate => StateLimit::Stop(0),
+    }
+}
+
+// Nodes of the assignment search in exclusive mode, the approximation keeps the assignment of the bins one by one
+fn get_max_assignment_nodes(options: &DimensionLessOptions) -> usize {
+    match options.approximation.mode {
+        SolveMode::Approximate => 0,
+        _ => options.approximation.max_assignment_nodes,
+    }
+}
+
+/*
+    Best selection of additional orders for one bin, with how far it may be from the optimum.
+    The exact knapsack keeps a limited number of states, lower in auto mode. Auto mode stops at the limit
+    and takes the approximation, exact mode keeps the most valuable states, then the approximation
+    gives the bound and the better of both selections is taken.
+*/
+fn select_orders_for_bin(
+    bin: &DLBinWithPackedOrders,
+    additional_orders: &[DimensionLessOrder],
+    options: &DimensionLessOptions,
+    rules: &ConflictRules,
+) -> Result<(Vec<DimensionLessOrder>, BinOptimality), InfeasibleReason> {
+    let selection = get_bin_selection(bin, additional_orders, options, rules)?;
+    Ok(select_from_bin_selection(bin, &selection, options))
+}
+
+fn select_from_bin_selection(
+    bin: &DLBinWithPackedOrders,
+    selection: &BinSelection,
+    options: &DimensionLessOptions,
+) -> (Vec<DimensionLessOrder>, BinOptimality) {
+    let approximation = &options.approximation;
+    let exact_selection = match approximation.mode {
+        SolveMode::Approximate => None,
+        _ => Some(knapsack_nd_float(
+            &selection.candidate_items,
+            &selection.capacities,
+            &selection.classes,
+            options.objective,
+            get_exact_state_limit(options),
+        )),
+    };
+    let get_value = |orders: &[DimensionLessOrder]| get_orders_value(orders, options.objective);
+
+    // the exact knapsack is its own upper bound
+    let (knapsack_orders, exact, upper_bound) = match exact_selection {
+        Some(KnapsackSelections {
+            mut selections,
+            complete: true,
+        }) => (selections.pop().unwrap_or_default(), true, None),
+        exact_selection => {
+            let approximate = knapsack_nd_approximate(
+                &selection.candidate_items,
+                &selection.capacities,
+                &selection.classes,
+                options.objective,
+                approximation.iterations,
+            );
+            let truncated_orders = exact_selection
+                .and_then(|exact_selection| exact_selection.selections.into_iter().next())
+                .unwrap_or_default();
+            let orders = match get_value(&truncated_orders) > get_value(&approximate.orders) {
+                true => truncated_orders,
+                false => approximate.orders,
+            };
+            // a selection reaching the bound is the best one, even if states were dropped
+            let exact = get_value(&orders) >= approximate.upper_bound;
+            (orders, exact, Some(approximate.upper_bound))
+        }
+    };
+
+    let forced_value = get_value(&selection.forced_orders);
+    let knapsack_value = get_value(&knapsack_orders);
+    let optimality = BinOptimality::new(
+        bin,
+        exact,
+        forced_value + knapsack_value,
+        forced_value + upper_bound.unwrap_or(knapsack_value).max(knapsack_value),
+    );
+
+    let mut selected_orders = selection.forced_orders.clone();
+    selected_orders.extend(knapsack_orders);
+    (selected_orders, optimality)
+}
+
+/*
+    Exclusive mode: every additional order goes to at most one bin and the value of all bins together counts.
+    Bins are first filled one after another, tightest bin first, each from the orders left.
+    Starting from there, the assignment search tries every bin for every group, within the node limit
+    of the exact knapsack. If the search is stopped, a bin is bounded by the approximation bound of its
+    selection on its own, as no assignment gives it more, and the sum of these bounds all bins.
+*/
+fn assign_orders_exclusively(
+    bins: &mut [DLBinWithPackedOrders],
+    additional_orders: &[DimensionLessOrder],
+    options: &DimensionLessOptions,
+    rules: &ConflictRules,
+) -> (Vec<InfeasibleBin>, Vec<BinOptimality>, AssignmentOptimality) {
+    let objective = options.objective;
+    let groups = get_order_groups(additional_orders);
+    let group_of_order: BTreeMap<&str, usize> = groups
+        .iter()
+        .enumerate()
+        .flat_map(|(index, group)| group.iter().map(move |o| (o.id.as_str(), index)))
+        .collect();
+    let group_of_item = |item: &KnapsackItem| group_of_order[item.orders[0].id.as_str()];
+
+    // orders every bin must include, with the other orders of their groups, are not offered to other bins
+    let reserved_ids: Vec<Vec<String>> = bins
+        .iter()
+        .map(|bin| {
+            get_forced_groups(bin, &groups)
+                .iter()
+                .flatten()
+                .map(|o| o.id.clone())
+                .collect()
+        })
+        .collect();
+    let mut infeasible_bins = Vec::new();
+    let mut selections: Vec<Option<BinSelection>> = Vec::new();
+    for (index, bin) in bins.iter().enumerate() {
+        let available_orders: Vec<DimensionLessOrder> = additional_orders
+            .iter()
+            .filter(|o| {
+                !reserved_ids
+                    .iter()
+                    .enumerate()
+                    .any(|(other, ids)| other != index && ids.contains(&o.id))
+            })
+            .cloned()
+            .collect();
+        match get_bin_selection(bin, &available_orders, options, rules) {
+            Ok(selection) => selections.push(Some(selection)),
+            Err(reason) => {
+                infeasible_bins.push(InfeasibleBin::new(bin, reason));
+                selections.push(None);
+            }
+        }
+    }
+
+    // first assignment, bins filled one after another with the groups no bin took yet
+    let mut bin_indices: Vec<usize> = (0..bins.len()).collect();
+    bin_indices.sort_by(|&a, &b| {
+        let policy = options.missing_volume;
+        let remaining_a = get_remaining_capacities(&bins[a], policy)[0];
+        let remaining_b = get_remaining_capacities(&bins[b], policy)[0];
+        remaining_a.total_cmp(&remaining_b)
+    });
+    let mut initial_assignment: Vec<Option<usize>> = vec![None; groups.len()];
+    for index in bin_indices {
+        let Some(selection) = &selections[index] else {
+            continue;
+        };
+        let open_selection = BinSelection {
+            forced_orders: Vec::new(),
+            candidate_items: selection
+                .candidate_items
+                .iter()
+                .filter(|item| initial_assignment[group_of_item(item)].is_none())
+                .cloned()
+                .collect(),
+            capacities: selection.capacities.clone(),
+            classes: selection.classes.clone(),
+        };
+        let (selected_orders, _) =
+            select_from_bin_selection(&bins[index], &open_selection, options);
+        for order in &selected_orders {
+            initial_assignment[group_of_order[order.id.as_str()]] = Some(index);
+        }
+    }
+
+    // infeasible bins take no groups
+    let assignment_bins: Vec<AssignmentBin> = selections
+        .iter()
+        .map(|selection| {
+            let mut items = vec![None; groups.len()];
+            let Some(selection) = selection else {
+                return AssignmentBin {
+                    capacities: vec![0.0],
+                    classes: Vec::new(),
+                    items,
+                };
+            };
+            for item in &selection.candidate_items {
+                items[group_of_item(item)] = Some(item);
+            }
+            AssignmentBin {
+                capacities: selection.capacities.clone(),
+                classes: selection.classes.clone(),
+                items,
+            }
+        })
+        .collect();
+    let group_values: Vec<f64> = groups
+        .iter()
+        .map(|group| group.iter().map(|o| o.get_objective_value(objective)).sum())
+        .collect();
+    let group_weights: Vec<f64> = groups
+        .iter()
+        .map(|group| group.iter().map(|o| o.weight).sum())
+        .collect();
+    let assignment = assign_groups_to_bins(
+        &assignment_bins,
+        &group_values,
+        &group_weights,
+        initial_assignment,
+        get_max_assignment_nodes(options),
+    );
+
+    // selected orders of every bin with its value and its bound on its own
+    let mut bin_results = Vec::new();
+    for (index, bin) in bins.iter_mut().enumerate() {
+        let Some(selection) = &selections[index] else {
+            continue;
+        };
+        let mut orders = selection.forced_orders.clone();
+        for (group, assigned_bin) in groups.iter().zip(&assignment.bins) {
+            if *assigned_bin == Some(index) {
+                orders.extend(group.iter().map(|&o| o.clone()));
+            }
+        }
+        let value = get_orders_value(&orders, objective);
+        let upper_bound = match assignment.complete {
+            true => value,
+            false => {
+                let forced_value = get_orders_value(&selection.forced_orders, objective);
+                let alone = knapsack_nd_approximate(
+                    &selection.candidate_items,
+                    &selection.capacities,
+                    &selection.classes,
+                    objective,
+                    options.approximation.iterations,
+                );
+                (forced_value + alone.upper_bound).max(value)
+            }
+        };
+        bin.additional_packed_orders = orders;
+        bin_results.push((index, value, upper_bound));
+    }
+
+    let value: f64 = bin_results.iter().map(|(_, value, _)| value).sum();
+    let forced_value: f64 = selections
+        .iter()
+        .flatten()
+        .map(|selection| get_orders_value(&selection.forced_orders, objective))
+        .sum();
+    // groups no bin can take are not part of any assignment
+    let offered_value: f64 = (0..groups.len())
+        .filter(|&group| assignment_bins.iter().any(|bin| bin.items[group].is_some()))
+        .map(|group| group_values[group].max(0.0))
+        .sum();
+    let upper_bound = bin_results
+        .iter()
+        .map(|(_, _, upper_bound)| upper_bound)
+        .sum::<f64>()
+        .min(forced_value + offered_value)
+        .max(value);
+    // an assignment reaching the bound is the best one, even if the search was stopped
+    let exact = assignment.complete || value >= upper_bound;
+
+    // with the assignment proven best, no bin can gain without another bin losing
+    let optimality = bin_results
+        .into_iter()
+        .map(|(index, value, upper_bound)| match exact {
+            true => BinOptimality::new(&bins[index], true, value, value),
+            false => BinOptimality::new(&bins[index], false, value, upper_bound),
+        })
+        .collect();
+    (
+        infeasible_bins,
+        optimality,
+        AssignmentOptimality::new(exact, value, upper_bound),
+    )
+}
+
+// Explains why an order was not assigned, looking at the whole group of the order
+fn get_unassigned_reason(
+    bins: &[DLBinWithPackedOrders],
+    order: &DimensionLessOrder,
+    group: &[&DimensionLessOrder],
+    policy: MissingVolumePolicy,
+    rules: &ConflictRules,
+) -> UnassignedReason {
+    let fits_any_bin = bins
+        .iter()
+        .any(|bin| fits_remaining_capacity(bin, order, policy));
+    if !fits_any_bin {
+        let volume_unknown = bins
+            .iter()
+            .any(|bin| get_order_usage(bin, order, policy).is_none());
+        return if volume_unknown {
+            UnassignedReason::MissingVolume
+        } else {
+            UnassignedReason::ExceedsCapacity
+        };
+    }
+
+    // the order fits on its own, the group does not fit any bin unless a bin is found below
+    let mut reason = UnassignedReason::GroupIncomplete;
+    for bin in bins {
+        match get_knapsack_item(bin, group, policy, rules) {
+            Ok(item) if fits_capacities(&item.usage, &get_remaining_capacities(bin, policy)) => {
+                if !intersects(&get_packed_classes(bin, rules), &item.conflicts) {
+                    return UnassignedReason::NotSelected;
+                }
+                reason = UnassignedReason::Conflict;
+            }
+            Err(UnassignedReason::Conflict) => reason = UnassignedReason::Conflict,
+            _ => {}
+        }
+    }
+    reason
+}
+
+/*
+    By default every bin selects from all additional orders, so one order may be offered to several bins.
+    In exclusive mode every order goes to at most one bin, see assign_orders_exclusively.
+    A bin whose must include orders cannot be loaded gets no additional orders and is reported as infeasible.
+*/
+pub fn pack_max_dimensionless_orders(
+    mut dl_bins_with_packed_orders: Vec<DLBinWithPackedOrders>,
+    additional_orders: Vec<DimensionLessOrder>,
+    options: &DimensionLessOptions,
+) -> DLPackingResult {
+    let rules = ConflictRules::new(&options.conflicts);
+    let mut infeasible_bins = Vec::new();
+    let mut optimality = Vec::new();
+    let mut assignment_optimality = None;
+
+    if options.exclusive {
+        let (exclusive_infeasible_bins, exclusive_optimality, total_optimality) =
+            assign_orders_exclusively(
+                &mut dl_bins_with_packed_orders,
+                &additional_orders,
+                options,
+                &rules,
+            );
+        infeasible_bins = exclusive_infeasible_bins;
+        optimality = exclusive_optimality;
+        assignment_optimality = Some(total_optimality);
+    } else {
+        // For each bin, select additional orders to pack
+        for bin in dl_bins_with_packed_orders.iter_mut() {
+            match select_orders_for_bin(bin, &additional_orders, options, &rules) {
+                Ok((selected_orders, bin_optimality)) => {
+                    bin.additional_packed_orders = selected_orders;
+                    optimality.push(bin_optimality);
+                }
+                Err(reason) => infeasible_bins.push(InfeasibleBin::new(bin, reason)),
+            }
+        }
+    }
+
+    // Orders that no bin received
+    let unassigned_orders = get_order_groups(&additional_orders)
+        .iter()
+        .flat_map(|group| group.iter().map(move |&o| (o, group)))
+        .filter(|(o, _)| {
+            !dl_bins_with_packed_orders.iter().any(|bin| {
+                bin.additional_packed_orders
+                    .iter()
+                    .any(|assigned| assigned.id == o.id)
+            })
+        })
+        .map(|(o, group)| {
+            let reason = get_unassigned_reason(
+                &dl_bins_with_packed_orders,
+                o,
+                group,
+                options.missing_volume,
+                &rules,
+            );
+            UnassignedOrder::new(o, reason)
+        })
+        .collect();
+
+    // Remove bins that have no additional orders to pack
+    dl_bins_with_packed_orders.retain(|bin| !bin.additional_packed_orders.is_empty());
+
+    DLPackingResult {
+        bins: dl_bins_with_packed_orders,
+        unassigned_orders,
+        infeasible_bins,
+        optimality,
+        assignment_optimality,
+    }
+}
+
+// Alternative with the bin utilisation after loading the packed orders and the selected orders
+fn get_alternative(
+    bin: &DLBinWithPackedOrders,
+    orders: Vec<DimensionLessOrder>,
+    options: &DimensionLessOptions,
+) -> DLAlternative {
+    let policy = options.missing_volume;
+    let mut used = get_remaining_capacities(bin, policy);
+    let mut capacities = vec![bin.max_weight];
+    capacities.extend(bin.max_volume);
+    for (used, capacity) in used.iter_mut().zip(&capacities) {
+        *used = capacity - *used;
+    }
+    for order in &orders {
+        let usage = get_order_usage(bin, order, policy).unwrap_or_default();
+        for (used, order_used) in used.iter_mut().zip(usage) {
+            *used += order_used;
+        }
+    }
+
+    // a bin without capacity holds nothing, it counts as unused
+    let get_share = |used: f64, capacity: f64| match capacity > 0.0 {
+        true => used / capacity,
+        false => 0.0,
+    };
+    DLAlternative {
+        value: orders
+            .iter()
+            .map(|o| o.get_objective_value(options.objective))
+            .sum(),
+        weight_utilisation: get_share(used[0], bin.max_weight),
+        volume_utilisation: bin
+            .max_volume
+            .map(|max_volume| get_share(used[1], max_volume)),
+        orders,
+    }
+}
+
+/*
+    Alternative selections of additional orders for every bin, each bin is looked at on its own.
+    Top k lists the best selections by the objective,
+    the Pareto front lists the selections no other selection beats by being at least as heavy with less volume.
+*/
+pub fn get_dimensionless_alternatives(
+    dl_bins_with_packed_orders: Vec<DLBinWithPackedOrders>,
+    additional_orders: Vec<DimensionLessOrder>,
+    options: &DimensionLessOptions,
+) -> DLAlternativesResult {
+    let rules = ConflictRules::new(&options.conflicts);
+    let alternatives_options = &options.alternatives;
+    let mut bins = Vec::new();
+    let mut infeasible_bins = Vec::new();
+
+    for bin in &dl_bins_with_packed_orders {
+        let selection = match get_bin_selection(bin, &additional_orders, options, &rules) {
+            Ok(selection) => selection,
+            Err(reason) => {
+                infeasible_bins.push(InfeasibleBin::new(bin, reason));
+                continue;
+            }
+        };
+
+        let KnapsackSelections {
+            selections,
+            complete,
+        } = match alternatives_options.mode {
+            AlternativesMode::TopK => knapsack_nd_top_k(
+                &selection.candidate_items,
+                &selection.capacities,
+                &selection.classes,
+                options.objective,
+                alternatives_options.count,
+                alternatives_options.max_states,
+            ),
+            AlternativesMode::ParetoFront => {
+                // weight and, if the bin limits it, volume
+                let front_dimensions = 1 + bin.max_volume.iter().count();
+                knapsack_nd_pareto(
+                    &selection.candidate_items,
+                    &selection.capacities,
+                    &selection.classes,
+                    front_dimensions,
+                    alternatives_options.count,
+                    alternatives_options.max_states,
+                )
+            }
+        };
+
+        let alternatives = selections
+            .into_iter()
+            .map(|selected_orders| {
+                let mut orders = selection.forced_orders.clone();
+                orders.extend(selected_orders);
+                get_alternative(bin, orders, options)
+            })
+            .collect();
+        bins.push(DLBinAlternatives {
+            id: bin.id.clone(),
+            alternatives,
+            complete,
+        });
+    }
+
+    DLAlternativesResult {
+        bins,
+        infeasible_bins,
+    }
+}
+
+fn get_bin_cost(bin: &DLBinWithPackedOrders) -> f64 {
+    bin.cost.unwrap_or(1.0)
+}
+
+/*
+    Size of an order against the largest bin type, used to compare what bin types take in.
+    Count breaks ties, so orders without weight and volume are loaded too.
+*/
+fn get_size_objective(bin_types: &[DLBinWithPackedOrders]) -> DimensionLessObjective {
+    let reciprocal = |max: f64| if max > 0.0 { 1.0 / max } else { 0.0 };
+    let max_weight = bin_types
+        .iter()
+        .map(|bin| bin.max_weight)
+        .fold(0.0, f64::max);
+    let max_volume = bin_types
+        .iter()
+        .filter_map(|bin| bin.max_volume)
+        .fold(0.0, f64::max);
+    DimensionLessObjective::Weighted(ObjectiveFactors {
+        weight: reciprocal(max_weight),
+        volume: reciprocal(max_volume),
+        count: 1e-6,
+        value: 0.0,
+    })
+}
+
+// Check if all orders together fit an empty bin of the type, next to its packed orders
+fn fits_bin_type(
+    bin_type: &DLBinWithPackedOrders,
+    orders: &[DimensionLessOrder],
+    policy: MissingVolumePolicy,
+    rules: &ConflictRules,
+) -> bool {
+    let orders: Vec<&DimensionLessOrder> = orders.iter().collect();
+    match get_knapsack_item(bin_type, &orders, policy, rules) {
+        Ok(item) => {
+            fits_capacities(&item.usage, &get_remaining_capacities(bin_type, policy))
+                && !intersects(&get_packed_classes(bin_type, rules), &item.conflicts)
+        }
+        Err(_) => false,
+    }
+}
+
+/*
+    Cheapest cost to cover the total usage of one resource with fractional bins, each type up to its count.
+    capacity gives the capacity of a bin type for the resource, None if the type does not limit it.
+*/
+fn get_fractional_cover_cost(
+    bin_types: &[DLBinWithPackedOrders],
+    total_usage: f64,
+    capacity: impl Fn(&DLBinWithPackedOrders) -> Option<f64>,
+) -> f64 {
+    let mut by_unit_cost: Vec<(f64, f64, Option<usize>)> = Vec::new();
+    for bin_type in bin_types {
+        match capacity(bin_type) {
+            // a type without limit covers everything with a fraction of a bin
+            None => return 0.0,
+            Some(capacity) if capacity > 0.0 => {
+                by_unit_cost.push((get_bin_cost(bin_type) / capacity, capacity, bin_type.count))
+            }
+            Some(_) => {}
+        }
+    }
+    by_unit_cost.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let mut remaining = total_usage;
+    let mut cost = 0.0;
+    for (unit_cost, capacity, count) in by_unit_cost {
+        if remaining <= 0.0 {
+            break;
+        }
+        let covered = match count {
+            Some(count) => remaining.min(capacity * count as f64),
+            None => remaining,
+        };
+        cost += covered * unit_cost;
+        remaining -= covered;
+    }
+    cost
+}
+
+/*
+    Lower bound of the cost to pack the orders.
+    Each of weight and volume alone must be covered by the opened bins, splitting bins allowed.
+    Only given volumes are counted, estimated volumes depend on the bin type.
+*/
+fn get_cost_lower_bound(
+    bin_types: &[DLBinWithPackedOrders],
+    orders: &[&DimensionLessOrder],
+) -> f64 {
+    let total_weight: f64 = orders.iter().map(|o| o.weight).sum();
+    let total_volume: f64 = orders.iter().filter_map(|o| o.volume).sum();
+    let weight_cost = get_fractional_cover_cost(bin_types, total_weight, |bin| {
+        Some(bin.max_weight - bin.packed_orders.iter().map(|o| o.weight).sum::<f64>())
+    });
+    let volume_cost = get_fractional_cover_cost(bin_types, total_volume, |bin| {
+        let packed_volume: f64 = bin.packed_orders.iter().filter_map(|o| o.volume).sum();
+        bin.max_volume.map(|max_volume| max_volume - packed_volume)
+    });
+    weight_cost.max(volume_cost)
+}
+
+/*
+    Opens bins from the bin types and assigns the orders, aiming for the lowest total cost.
+    Bins are opened one after another, each time the type that takes in the most order size per cost,
+    filled by the knapsack. Afterwards every opened bin is swapped for the cheapest type that still
+    takes all of its orders. Must include orders of the bin types are not used.
+*/
+pub fn pack_orders_into_dimensionless_bins(
+    bin_types: Vec<DLBinWithPackedOrders>,
+    orders: Vec<DimensionLessOrder>,
+    options: &DimensionLessOptions,
+) -> DLBinPackingResult {
+    let rules = ConflictRules::new(&options.conflicts);
+    let policy = options.missing_volume;
+    let bin_types: Vec<DLBinWithPackedOrders> = bin_types
+        .into_iter()
+        .map(|mut bin_type| {
+            bin_type.must_include.clear();
+            bin_type.additional_packed_orders.clear();
+            bin_type
+        })
+        .collect();
+    let size_objective = get_size_objective(&bin_types);
+    let size_options = DimensionLessOptions {
+        objective: size_objective,
+        ..options.clone()
+    };
+
+    let mut remaining_counts: Vec<Option<usize>> =
+        bin_types.iter().map(|bin_type| bin_type.count).collect();
+    let mut remaining_orders = orders.clone();
+    // type index and orders of every opened bin
+    let mut opened_bins: Vec<(usize, Vec<DimensionLessOrder>)> = Vec::new();
+
+    while !remaining_orders.is_empty() {
+        let mut best: Option<(f64, usize, Vec<DimensionLessOrder>)> = None;
+        for (index, bin_type) in bin_types.iter().enumerate() {
+            if remaining_counts[index] == Some(0) {
+                continue;
+            }
+            let Ok((selected_orders, _)) =
+                select_orders_for_bin(bin_type, &remaining_orders, &size_options, &rules)
+            else {
+                continue;
+            };
+            if selected_orders.is_empty() {
+                continue;
+            }
+            let size: f64 = selected_orders
+                .iter()
+                .map(|o| o.get_objective_value(size_objective))
+                .sum();
+            let cost = get_bin_cost(bin_type);
+            let size_per_cost = if cost > 0.0 {
+                size / cost
+            } else {
+                f64::INFINITY
+            };
+            if best
+                .as_ref()
+                .is_none_or(|(best_size_per_cost, _, _)| size_per_cost > *best_size_per_cost)
+            {
+                best = Some((size_per_cost, index, selected_orders));
+            }
+        }
+
+        let Some((_, index, selected_orders)) = best else {
+            break;
+        };
+        if let Some(count) = remaining_counts[index].as_mut() {
+            *count -= 1;
+        }
+        remaining_orders.retain(|o| !selected_orders.iter().any(|s| s.id == o.id));
+        opened_bins.push((index, selected_orders));
+    }
+
+    // Swap opened bins for cheaper types that take all of their orders
+    for (index, bin_orders) in opened_bins.iter_mut() {
+        let cheaper_type = (0..bin_types.len())
+            .filter(|&other| remaining_counts[other] != Some(0))
+            .filter(|&other| get_bin_cost(&bin_types[other]) < get_bin_cost(&bin_types[*index]))
+            .filter(|&other| fits_bin_type(&bin_types[other], bin_orders, policy, &rules))
+            .min_by(|&a, &b| get_bin_cost(&bin_types[a]).total_cmp(&get_bin_cost(&bin_types[b])));
+        if let Some(other) = cheaper_type {
+            if let Some(count) = remaining_counts[*index].as_mut() {
+                *count += 1;
+            }
+            if let Some(count) = remaining_counts[other].as_mut() {
+                *count -= 1;
+            }
+            *index = other;
+        }
+    }
+
+    let assigned_orders: Vec<&DimensionLessOrder> =
+        opened_bins.iter().flat_map(|(_, orders)| orders).collect();
+    let lower_bound = get_cost_lower_bound(&bin_types, &assigned_orders);
+
+    let unassigned_orders = get_order_groups(&orders)
+        .iter()
+        .flat_map(|group| group.iter().map(move |&o| (o, group)))
+        .filter(|(o, _)| remaining_orders.iter().any(|r| r.id == o.id))
+        .map(|(o, group)| {
+            let reason = match get_unassigned_reason(&bin_types, o, group, policy, &rules) {
+                // every order a bin type takes is loaded while bins are left
+                UnassignedReason::NotSelected => UnassignedReason::BinsExhausted,
+                reason => reason,
+            };
+            UnassignedOrder::new(o, reason)
+        })
+        .collect();
+
+    let bins: Vec<DLBinWithPackedOrders> = opened_bins
+        .into_iter()
+        .map(|(index, orders)| {
+            let mut bin = bin_types[index].clone();
+            bin.additional_packed_orders = orders;
+            bin
+        })
+        .collect();
+    let total_cost: f64 = bins.iter().map(get_bin_cost).sum();
+    let gap = if total_cost > 0.0 {
+        (total_cost - lower_bound).max(0.0) / total_cost
+    } else {
+        0.0
+    };
+
+    DLBinPackingResult {
+        bins,
+        total_cost,
+        lower_bound,
+        gap,
+        unassigned_orders,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bin_packing::options::{AlternativesOptions, ApproximationOptions};
+    use crate::bin_packing::solver::random::Random;
+    use serde_json::json;
+    use std::time::{Duration, Instant};
+
+    fn get_bins(max_weights: &[f64]) -> Vec<DLBinWithPackedOrders> {
+        max_weights
+            .iter()
+            .enumerate()
+            .map(|(index, max_weight)| {
+                serde_json::from_value(
+                    json!({ "id": format!("bin{index}"), "max_weight": max_weight }),
+                )
+                .unwrap()
+            })
+            .collect()
+    }
+
+    fn get_orders(weights: &[f64]) -> Vec<DimensionLessOrder> {
+        weights
+            .iter()
+            .enumerate()
+            .map(|(index, weight)| {
+                serde_json::from_value(json!({ "id": format!("order{index}"), "weight": weight }))
+                    .unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn exclusive_orders_are_assigned_across_all_bins() {
+        // filling the small bin first with 4 + 1 leaves 9 for the large one, 14 in total, the best assignment reaches 15
+        let options = DimensionLessOptions {
+            exclusive: true,
+            ..Default::default()
+        };
+        let result = pack_max_dimensionless_orders(
+            get_bins(&[5.0, 10.0]),
+            get_orders(&[4.0, 3.0, 9.0, 1.0, 5.0, 6.0]),
+            &options,
+        );
+        let assignment = result.assignment_optimality.unwrap();
+        assert!(assignment.exact);
+        assert_eq!(assignment.value, 15.0);
+
+        let mut assigned_ids: Vec<&str> = result
+            .bins
+            .iter()
+            .flat_map(|bin| &bin.additional_packed_orders)
+            .map(|order| order.id.as_str())
+            .collect();
+        let assigned_count = assigned_ids.len();
+        assigned_ids.sort();
+        assigned_ids.dedup();
+        assert_eq!(assigned_ids.len(), assigned_count);
+        for bin in &result.bins {
+            let weight: f64 = bin.additional_packed_orders.iter().map(|o| o.weight).sum();
+            assert!(weight <= bin.max_weight);
+        }
+    }
+
+    #[test]
+    fn approximate_bound_holds_the_best_selection() {
+        let options = DimensionLessOptions {
+            approximation: ApproximationOptions {
+                mode: SolveMode::Approximate,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let result = pack_max_dimensionless_orders(
+            get_bins(&[10.0]),
+            get_orders(&[4.0, 3.0, 9.0, 1.0, 5.0, 6.0]),
+            &options,
+        );
+        let optimality = &result.optimality[0];
+        assert!(optimality.value <= 10.0);
+        assert!(optimality.upper_bound >= 10.0);
+    }
+
+    // Orders with whole random weights of 1 to 100 and volumes of 1 to 10
+    fn get_random_orders(count: usize) -> Vec<DimensionLessOrder> {
+        let mut random = Random::new(11);
+        (0..count)
+            .map(|index| {
+                let weight = (1.0 + random.next_f32() * 99.0).round();
+                let volume = (1.0 + random.next_f32() * 9.0).round();
+                serde_json::from_value(
+                    json!({ "id": format!("order{index}"), "weight": weight, "volume": volume }),
+                )
+                .unwrap()
+            })
+            .collect()
+    }
+
+    fn get_alternatives_options(mode: AlternativesMode) -> DimensionLessOptions {
+        DimensionLessOptions {
+            alternatives: AlternativesOptions {
+                mode,
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn alternatives_of_many_orders_are_complete_and_fast() {
+        let bin: DLBinWithPackedOrders = serde_json::from_value(
+            json!({ "id": "bin", "max_weight": 1000.0, "max_volume": 100.0 }),
+        )
+        .unwrap();
+        for mode in [AlternativesMode::TopK, AlternativesMode::ParetoFront] {
+            let start = Instant::now();
+            let result = get_dimensionless_alternatives(
+                vec![bin.clone()],
+                get_random_orders(60),
+                &get_alternatives_options(mode),
+            );
+            // a few hundred milliseconds in a release build
+            assert!(start.elapsed() < Duration::from_secs(5));
+            assert!(result.bins[0].complete);
+            assert!(!result.bins[0].alternatives.is_empty());
+        }
+    }
+
+    #[test]
+    fn pareto_front_trades_weight_for_volume() {
+        let bin: DLBinWithPackedOrders =
+            serde_json::from_value(json!({ "id": "bin", "max_weight": 300.0, "max_volume": 30.0 }))
+                .unwrap();
+        let result = get_dimensionless_alternatives(
+            vec![bin],
+            get_random_orders(20),
+            &get_alternatives_options(AlternativesMode::ParetoFront),
+        );
+        let alternatives = &result.bins[0].alternatives;
+        assert!(alternatives.len() > 1);
+        // heaviest first, every lighter alternative uses less volume
+        for pair in alternatives.windows(2) {
+            assert!(pair[0].weight_utilisation > pair[1].weight_utilisation);
+            assert!(pair[0].volume_utilisation > pair[1].volume_utilisation);
+        }
+    }
+
+    #[test]
+    fn bin_without_capacity_counts_as_unused() {
+        let bin: DLBinWithPackedOrders =
+            serde_json::from_value(json!({ "id": "bin", "max_weight": 0.0, "max_volume": 0.0 }))
+                .unwrap();
+        let result = get_dimensionless_alternatives(
+            vec![bin],
+            get_orders(&[0.0]),
+            &get_alternatives_options(AlternativesMode::TopK),
+        );
+        let alternative = &result.bins[0].alternatives[0];
+        assert_eq!(alternative.weight_utilisation, 0.0);
+        assert_eq!(alternative.volume_utilisation, Some(0.0));
+    }
+
+    // Orders with a weight and a value each
+    fn get_valued_orders(weights_and_values: &[(f64, f64)]) -> Vec<DimensionLessOrder> {
+        weights_and_values
+            .iter()
+            .enumerate()
+            .map(|(index, (weight, value))| {
+                serde_json::from_value(
+                    json!({ "id": format!("order{index}"), "weight": weight, "value": value }),
+                )
+                .unwrap()
+            })
+            .collect()
+    }
+
+    fn get_assigned_ids(result: &DLPackingResult) -> Vec<&str> {
+        let mut ids: Vec<&str> = result
+            .bins
+            .iter()
+            .flat_map(|bin| &bin.additional_packed_orders)
+            .map(|order| order.id.as_str())
+            .collect();
+        ids.sort();
+        ids
+    }
+
+    #[test]
+    fn objective_decides_which_orders_are_loaded() {
+        // the heavy order fills the bin best, the two light ones are worth more
+        let orders = get_valued_orders(&[(9.0, 1.0), (4.0, 5.0), (4.0, 5.0)]);
+        let pack = |objective| {
+            let options = DimensionLessOptions {
+                objective,
+                ..Default::default()
+            };
+            pack_max_dimensionless_orders(get_bins(&[10.0]), orders.clone(), &options)
+        };
+
+        let result = pack(DimensionLessObjective::Weight);
+        assert_eq!(get_assigned_ids(&result), ["order0"]);
+        assert_eq!(result.optimality[0].value, 9.0);
+
+        let result = pack(DimensionLessObjective::Value);
+        assert_eq!(get_assigned_ids(&result), ["order1", "order2"]);
+        assert_eq!(result.optimality[0].value, 10.0);
+        assert!(result.optimality[0].exact);
+
+        let result = pack(DimensionLessObjective::Count);
+        assert_eq!(get_assigned_ids(&result), ["order1", "order2"]);
+        assert_eq!(result.optimality[0].value, 2.0);
+    }
+
+    #[test]
+    fn weighted_objective_sums_the_factors() {
+        let orders = get_valued_orders(&[(9.0, 1.0), (4.0, 5.0), (4.0, 5.0)]);
+        // 1.0 per kg and 2.0 per value: 9 + 2 = 11 for the heavy order, 2 * (4 + 10) = 28 for the light ones
+        let options: DimensionLessOptions = serde_json::from_value(json!({
+            "objective": { "weighted": { "weight": 1.0, "value": 2.0 } }
+        }))
+        .unwrap();
+        let result = pack_max_dimensionless_orders(get_bins(&[10.0]), orders, &options);
+        assert_eq!(get_assigned_ids(&result), ["order1", "order2"]);
+        assert_eq!(result.optimality[0].value, 28.0);
+    }
+
+    fn get_bin(bin: serde_json::Value) -> DLBinWithPackedOrders {
+        serde_json::from_value(bin).unwrap()
+    }
+
+    fn get_order(order: serde_json::Value) -> DimensionLessOrder {
+        serde_json::from_value(order).unwrap()
+    }
+
+    fn get_unassigned_reasons(result: &DLPackingResult) -> Vec<(&str, UnassignedReason)> {
+        result
+            .unassigned_orders
+            .iter()
+            .map(|order| (order.id.as_str(), order.reason))
+            .collect()
+    }
+
+    #[test]
+    fn named_resources_limit_the_selection() {
+        // the packed order already takes one of the three pallet spaces
+        let bin = get_bin(json!({
+            "id": "truck",
+            "max_weight": 100.0,
+            "max_resources": { "pallets": 3.0, "cold_slots": 1.0 },
+            "packed_orders": [{ "id": "packed", "weight": 1.0, "resources": { "pallets": 1.0 } }]
+        }));
+        let orders = vec![
+            get_order(
+                json!({ "id": "cold1", "weight": 20.0, "resources": { "pallets": 1.0, "cold_slots": 1.0 } }),
+            ),
+            get_order(
+                json!({ "id": "cold2", "weight": 20.0, "resources": { "pallets": 1.0, "cold_slots": 1.0 } }),
+            ),
+            get_order(json!({ "id": "dry1", "weight": 10.0, "resources": { "pallets": 1.0 } })),
+            get_order(json!({ "id": "dry2", "weight": 10.0, "resources": { "pallets": 1.0 } })),
+            get_order(json!({ "id": "oversized", "weight": 1.0, "resources": { "pallets": 3.0 } })),
+        ];
+        let result = pack_max_dimensionless_orders(vec![bin], orders, &Default::default());
+
+        let loaded = &result.bins[0].additional_packed_orders;
+        assert_eq!(loaded.len(), 2);
+        let get_used = |resource: &str| -> f64 {
+            loaded
+                .iter()
+                .map(|order| order.resources.get(resource).copied().unwrap_or(0.0))
+                .sum()
+        };
+        assert_eq!(get_used("pallets"), 2.0);
+        assert_eq!(get_used("cold_slots"), 1.0);
+        // one cold order and one dry order, the heaviest pair within the resources
+        assert_eq!(result.optimality[0].value, 30.0);
+        assert!(get_unassigned_reasons(&result)
+            .contains(&("oversized", UnassignedReason::ExceedsCapacity)));
+    }
+
+    #[test]
+    fn missing_volume_follows_the_policy() {
+        let bin = get_bin(
+            json!({ "id": "van", "max_weight": 100.0, "max_volume": 10.0, "density": 2.0 }),
+        );
+        let orders = vec![
+            get_order(json!({ "id": "unmeasured", "weight": 12.0 })),
+            get_order(json!({ "id": "measured", "weight": 30.0, "volume": 5.0 })),
+        ];
+        let pack = |missing_volume| {
+            let options = DimensionLessOptions {
+                missing_volume,
+                ..Default::default()
+            };
+            pack_max_dimensionless_orders(vec![bin.clone()], orders.clone(), &options)
+        };
+
+        let result = pack(MissingVolumePolicy::Reject);
+        assert_eq!(get_assigned_ids(&result), ["measured"]);
+        assert_eq!(
+            get_unassigned_reasons(&result),
+            [("unmeasured", UnassignedReason::MissingVolume)]
+        );
+
+        let result = pack(MissingVolumePolicy::Zero);
+        assert_eq!(get_assigned_ids(&result), ["measured", "unmeasured"]);
+
+        // 12 / 2.0 gives a volume of 6, both orders together exceed the volume limit
+        let result = pack(MissingVolumePolicy::Density);
+        assert_eq!(get_assigned_ids(&result), ["measured"]);
+        assert_eq!(
+            get_unassigned_reasons(&result),
+            [("unmeasured", UnassignedReason::NotSelected)]
+        );
+    }
+
+    #[test]
+    fn density_policy_rejects_without_a_bin_density() {
+        let bin = get_bin(json!({ "id": "van", "max_weight": 100.0, "max_volume": 10.0 }));
+        let options = DimensionLessOptions {
+            missing_volume: MissingVolumePolicy::Density,
+            ..Default::default()
+        };
+        let result = pack_max_dimensionless_orders(
+            vec![bin],
+            vec![get_order(json!({ "id": "unmeasured", "weight": 12.0 }))],
+            &options,
+        );
+        assert!(result.bins.is_empty());
+        assert_eq!(
+            get_unassigned_reasons(&result),
+            [("unmeasured", UnassignedReason::MissingVolume)]
+        );
+    }
+
+    #[test]
+    fn groups_are_loaded_all_or_none() {
+        let orders = vec![
+            get_order(json!({ "id": "split1", "weight": 6.0, "group": "customer" })),
+            get_order(json!({ "id": "split2", "weight": 6.0, "group": "customer" })),
+            get_order(json!({ "id": "single", "weight": 5.0 })),
+        ];
+        // the group weighs 12 together, more than the bin takes
+        let result =
+            pack_max_dimensionless_orders(get_bins(&[10.0]), orders.clone(), &Default::default());
+        assert_eq!(get_assigned_ids(&result), ["single"]);
+        assert_eq!(
+            get_unassigned_reasons(&result),
+            [
+                ("split1", UnassignedReason::GroupIncomplete),
+                ("split2", UnassignedReason::GroupIncomplete)
+            ]
+        );
+
+        let result = pack_max_dimensionless_orders(get_bins(&[12.0]), orders, &Default::default());
+        assert_eq!(get_assigned_ids(&result), ["split1", "split2"]);
+    }
+
+    #[test]
+    fn must_include_orders_are_loaded_first() {
+        let bin = get_bin(json!({ "id": "truck", "max_weight": 10.0, "must_include": ["light"] }));
+        // on its own the heavy order fills the bin best
+        let orders = vec![
+            get_order(json!({ "id": "heavy", "weight": 10.0 })),
+            get_order(json!({ "id": "light", "weight": 2.0 })),
+        ];
+        let result = pack_max_dimensionless_orders(vec![bin], orders, &Default::default());
+        assert_eq!(get_assigned_ids(&result), ["light"]);
+        assert!(result.infeasible_bins.is_empty());
+    }
+
+    #[test]
+    fn infeasible_bins_report_their_reason() {
+        let options = DimensionLessOptions {
+            conflicts: vec![("food".to_string(), "chemicals".to_string())],
+            ..Default::default()
+        };
+        let bins = vec![
+            get_bin(json!({ "id": "missing", "max_weight": 10.0, "must_include": ["unknown"] })),
+            get_bin(json!({ "id": "small", "max_weight": 10.0, "must_include": ["heavy"] })),
+            get_bin(json!({
+                "id": "food_truck",
+                "max_weight": 100.0,
+                "must_include": ["solvent"],
+                "packed_orders": [{ "id": "bread", "weight": 1.0, "category": "food" }]
+            })),
+        ];
+        let orders = vec![
+            get_order(json!({ "id": "heavy", "weight": 11.0 })),
+            get_order(json!({ "id": "solvent", "weight": 1.0, "category": "chemicals" })),
+        ];
+        let result = pack_max_dimensionless_orders(bins, orders, &options);
+
+        let reasons: Vec<(&str, &InfeasibleReason)> = result
+            .infeasible_bins
+            .iter()
+            .map(|bin| (bin.id.as_str(), &bin.reason))
+            .collect();
+        assert_eq!(
+            reasons,
+            [
+                (
+                    "missing",
+                    &InfeasibleReason::NotAvailable {
+                        order_id: "unknown".to_string()
+                    }
+                ),
+                ("small", &InfeasibleReason::ExceedsCapacity),
+                (
+                    "food_truck",
+                    &InfeasibleReason::Conflict {
+                        order_id: "solvent".to_string()
+                    }
+                ),
+            ]
+        );
+        assert!(result.bins.is_empty());
+    }
+
+    #[test]
+    fn conflicting_orders_never_share_a_bin() {
+        let options = DimensionLessOptions {
+            conflicts: vec![("food".to_string(), "chemicals".to_string())],
+            ..Default::default()
+        };
+        let orders = vec![
+            get_order(json!({ "id": "bread", "weight": 4.0, "category": "food" })),
+            get_order(json!({ "id": "solvent", "weight": 5.0, "category": "chemicals" })),
+            get_order(json!({ "id": "tools", "weight": 1.0 })),
+        ];
+        // bread and solvent together would fill the bin best
+        let result = pack_max_dimensionless_orders(get_bins(&[10.0]), orders.clone(), &options);
+        assert_eq!(get_assigned_ids(&result), ["solvent", "tools"]);
+
+        // with food already on board the chemicals conflict with the load
+        let bin = get_bin(json!({
+            "id": "food_truck",
+            "max_weight": 100.0,
+            "packed_orders": [{ "id": "cheese", "weight": 1.0, "category": "food" }]
+        }));
+        let result = pack_max_dimensionless_orders(vec![bin], orders, &options);
+        assert_eq!(get_assigned_ids(&result), ["bread", "tools"]);
+        assert_eq!(
+            get_unassigned_reasons(&result),
+            [("solvent", UnassignedReason::Conflict)]
+        );
+    }
+
+    #[test]
+    fn small_inputs_stay_exact_and_large_ones_report_their_gap() {
+        let bin = get_bin(json!({ "id": "bin", "max_weight": 2000.0, "max_volume": 200.0 }));
+        let pack = |count, mode| {
+            let options = DimensionLessOptions {
+                approximation: ApproximationOptions {
+                    mode,
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+            pack_max_dimensionless_orders(vec![bin.clone()], get_random_orders(count), &options)
+        };
+
+        let optimality = &pack(10, SolveMode::Auto).optimality[0];
+        assert!(optimality.exact);
+        assert_eq!(optimality.gap, 0.0);
+
+        let exact = &pack(200, SolveMode::Exact).optimality[0];
+        let approximate = &pack(200, SolveMode::Approximate).optimality[0];
+        // the approximation is only reported as exact once it reaches its bound
+        assert_eq!(
+            approximate.exact,
+            approximate.value >= approximate.upper_bound
+        );
+        assert!(approximate.value <= exact.value);
+        assert!(approximate.upper_bound >= exact.value);
+        let gap = (approximate.upper_bound - approximate.value) / approximate.upper_bound;
+        assert!((approximate.gap - gap).abs() < 1e-12);
+        // many small orders leave little room between the greedy selection and the bound
+        assert!(approximate.gap < 0.01, "{}", approximate.gap);
+    }
+
+    fn get_opened_ids(result: &DLBinPackingResult) -> Vec<&str> {
+        result.bins.iter().map(|bin| bin.id.as_str()).collect()
+    }
+
+    #[test]
+    fn cheapest_bins_take_all_orders() {
+        // the large type costs less per kg
+        let bin_types = vec![
+            get_bin(json!({ "id": "small", "max_weight": 10.0, "cost": 1.0 })),
+            get_bin(json!({ "id": "large", "max_weight": 30.0, "cost": 2.0 })),
+        ];
+        let result = pack_orders_into_dimensionless_bins(
+            bin_types,
+            get_orders(&[5.0; 6]),
+            &Default::default(),
+        );
+        assert_eq!(get_opened_ids(&result), ["large"]);
+        assert_eq!(result.bins[0].additional_packed_orders.len(), 6);
+        assert_eq!(result.total_cost, 2.0);
+        assert_eq!(result.lower_bound, 2.0);
+        assert_eq!(result.gap, 0.0);
+        assert!(result.unassigned_orders.is_empty());
+    }
+
+    #[test]
+    fn bin_counts_limit_the_opened_bins() {
+        let bin_types = vec![
+            get_bin(json!({ "id": "small", "max_weight": 10.0, "cost": 1.0 })),
+            get_bin(json!({ "id": "large", "max_weight": 30.0, "cost": 2.0, "count": 1 })),
+        ];
+        let result = pack_orders_into_dimensionless_bins(
+            bin_types,
+            get_orders(&[5.0; 9]),
+            &Default::default(),
+        );
+        let opened_ids = get_opened_ids(&result);
+        assert_eq!(opened_ids.iter().filter(|&&id| id == "large").count(), 1);
+        assert!(result.unassigned_orders.is_empty());
+        for bin in &result.bins {
+            let weight: f64 = bin.additional_packed_orders.iter().map(|o| o.weight).sum();
+            assert!(weight <= bin.max_weight);
+        }
+        // one large bin for 30 kg, the other 15 kg need two small ones
+        assert_eq!(result.total_cost, 4.0);
+        // the fractional cover takes a small bin and a half for the rest
+        assert_eq!(result.lower_bound, 3.5);
+        assert_eq!(result.gap, (4.0 - 3.5) / 4.0);
+    }
+
+    #[test]
+    fn orders_without_a_bin_are_returned_with_their_reason() {
+        let bin_types = vec![get_bin(
+            json!({ "id": "small", "max_weight": 10.0, "count": 1 }),
+        )];
+        let result = pack_orders_into_dimensionless_bins(
+            bin_types,
+            get_orders(&[5.0, 5.0, 5.0, 11.0]),
+            &Default::default(),
+        );
+        assert_eq!(get_opened_ids(&result), ["small"]);
+        assert_eq!(result.total_cost, 1.0);
+        let reasons: Vec<(&str, UnassignedReason)> = result
+            .unassigned_orders
+            .iter()
+            .map(|order| (order.id.as_str(), order.reason))
+            .collect();
+        // one of the light orders is left for a second bin, the heavy one fits no bin type
+        assert_eq!(reasons.len(), 2);
+        assert_eq!(reasons[0].1, UnassignedReason::BinsExhausted);
+        assert_eq!(reasons[1], ("order3", UnassignedReason::ExceedsCapacity));
+    }
+}
@@ -0,0 +1,190 @@
+/*
+    Extreme point placement (Crainic, Perboli and Tadei, 2008).
+    The corners of a placed item are projected onto the faces of the packed items or the bin walls,
+    which reaches positions in gaps that plain corner pivots never offer.
+*/
+use crate::bin_packing::bin::Bin;
+
+const TOLERANCE: f32 = 1e-4;
+
+// Check if a coordinate lies within [start, start + length)
+fn within(coordinate: f32, start: f32, length: f32) -> bool {
+    coordinate >= start - TOLERANCE && coordinate < start + length - TOLERANCE
+}
+
+// Boxes of the packed items as (dimension, position)
+fn get_packed_boxes(bin: &Bin) -> Vec<([f32; 3], [f32; 3])> {
+    bin.packed_items
+        .iter()
+        .map(|item| (item.get_rotated_dimension(&item.rotation), item.position))
+        .collect()
+}
+
+/*
+    Moves the point along the negative direction of the axis until it meets the face of a packed item
+    or the bin wall, and returns the new coordinate on that axis
+*/
+fn project(point: &[f32; 3], axis: usize, boxes: &[([f32; 3], [f32; 3])]) -> f32 {
+    let others: Vec<usize> = (0..3).filter(|&other| other != axis).collect();
+    boxes
+        .iter()
+        .filter(|(dimension, position)| {
+            others
+                .iter()
+                .all(|&other| within(point[other], position[other], dimension[other]))
+        })
+        .map(|(dimension, position)| position[axis] + dimension[axis])
+        .filter(|&face| face <= point[axis] + TOLERANCE)
+        .fold(0.0, f32::max)
+}
+
+fn is_inside_packed_item(point: &[f32; 3], boxes: &[([f32; 3], [f32; 3])]) -> bool {
+    boxes.iter().any(|(dimension, position)| {
+        (0..3).all(|axis| within(point[axis], position[axis], dimension[axis]))
+    })
+}
+
+fn is_inside_bin(bin: &Bin, point: &[f32; 3]) -> bool {
+    point[0] < bin.width - TOLERANCE
+        && point[1] < bin.height - TOLERANCE
+        && point[2] < bin.depth - TOLERANCE
+}
+
+/*
+    Returns the open extreme points after the last item of bin.packed_items was placed.
+    Points covered by the new item are dropped, and the list is sorted bottom-back-left (height, depth, width).
+*/
+pub fn get_extreme_points(bin: &Bin, open_points: &[[f32; 3]], stackable: bool) -> Vec<[f32; 3]> {
+    let boxes = get_packed_boxes(bin);
+    let Some((dimension, position)) = boxes.last() else {
+        return open_points.to_vec();
+    };
+
+    let mut new_points: Vec<[f32; 3]> = open_points.to_vec();
+
+    // corner along width, projected down and towards the front wall
+    let corner = [position[0] + dimension[0], position[1], position[2]];
+    new_points.push([corner[0], project(&corner, 1, &boxes), corner[2]]);
+    new_points.push([corner[0], corner[1], project(&corner, 2, &boxes)]);
+
+    // corner along depth, projected down and towards the side wall
+    let corner = [position[0], position[1], position[2] + dimension[2]];
+    new_points.push([corner[0], project(&corner, 1, &boxes), corner[2]]);
+    new_points.push([project(&corner, 0, &boxes), corner[1], corner[2]]);
+
+    // corner on top, projected towards the side and front walls
+    if stackable {
+        let corner = [position[0], position[1] + dimension[1], position[2]];
+        new_points.push([project(&corner, 0, &boxes), corner[1], corner[2]]);
+        new_points.push([corner[0], corner[1], project(&corner, 2, &boxes)]);
+    }
+
+    new_points.retain(|point| is_inside_bin(bin, point) && !is_inside_packed_item(point, &boxes));
+    new_points.sort_by(|a, b| {
+        a[1].total_cmp(&b[1])
+            .then(a[2].total_cmp(&b[2]))
+            .then(a[0].total_cmp(&b[0]))
+    });
+    new_points.dedup_by(|a, b| (0..3).all(|axis| (a[axis] - b[axis]).abs() <= TOLERANCE));
+    new_points
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bin_packing::bin::RawBin;
+    use crate::bin_packing::item::{Item, RawItem};
+    use crate::bin_packing::options::{PackingOptions, PlacementEngine};
+    use crate::bin_packing::order::RawOrder;
+    use crate::bin_packing::progress::SolverMonitor;
+    use crate::bin_packing::solver::pack_items_into_bin;
+
+    fn get_bin() -> Bin {
+        RawBin {
+            name: "container".to_string(),
+            width: 100.0,
+            height: 100.0,
+            depth: 100.0,
+            max_weight: 1000.0,
+            cog_envelope: None,
+            axles: None,
+        }
+        .convert_to_bin()
+    }
+
+    // Items with the dimensions [width, height, depth]
+    fn get_items(name: &str, quantity: i32, dimension: [f32; 3]) -> Vec<Item> {
+        RawOrder {
+            name: name.to_string(),
+            items: vec![RawItem {
+                name: "box".to_string(),
+                quantity,
+                width: dimension[0],
+                height: dimension[1],
+                depth: dimension[2],
+                weight: 1.0,
+                max_top_load: None,
+            }],
+            orientable: Some(false),
+            stackable: None,
+        }
+        .create_order_from_raw_order()
+        .items
+    }
+
+    fn place(bin: &mut Bin, dimension: [f32; 3], position: [f32; 3]) {
+        let item = get_items("box", 1, dimension).remove(0);
+        bin.packed_items.push(Item { position, ..item });
+    }
+
+    #[test]
+    fn corners_are_projected_down_and_onto_walls() {
+        let mut bin = get_bin();
+        place(&mut bin, [50.0, 50.0, 50.0], [0.0; 3]);
+        let points = get_extreme_points(&bin, &[[0.0; 3]], true);
+        assert_eq!(
+            points,
+            [[50.0, 0.0, 0.0], [0.0, 0.0, 50.0], [0.0, 50.0, 0.0]]
+        );
+
+        // the depth corner of the item on top drops to the floor
+        place(&mut bin, [50.0, 20.0, 50.0], [0.0, 50.0, 0.0]);
+        let points = get_extreme_points(&bin, &points, true);
+        assert_eq!(
+            points,
+            [
+                [50.0, 0.0, 0.0],
+                [0.0, 0.0, 50.0],
+                [50.0, 50.0, 0.0],
+                [0.0, 50.0, 50.0],
+                [0.0, 70.0, 0.0],
+            ]
+        );
+    }
+
+    #[test]
+    fn corners_are_projected_onto_item_faces() {
+        let mut bin = get_bin();
+        place(&mut bin, [20.0, 100.0, 100.0], [0.0; 3]);
+        place(&mut bin, [30.0, 30.0, 30.0], [50.0, 0.0, 0.0]);
+        let points = get_extreme_points(&bin, &[], true);
+        assert!(points.contains(&[20.0, 0.0, 30.0]));
+        assert!(points.contains(&[20.0, 30.0, 0.0]));
+        // without stacking no point is offered on top of the item
+        let points = get_extreme_points(&bin, &[], false);
+        assert!(points.iter().all(|point| point[1] == 0.0));
+    }
+
+    #[test]
+    fn extreme_points_fill_the_bin() {
+        let options = PackingOptions {
+            placement_engine: PlacementEngine::ExtremePoints,
+            ..Default::default()
+        };
+        let items = get_items("cubes", 8, [50.0, 50.0, 50.0]);
+        let mut bin = get_bin();
+        let unpacked = pack_items_into_bin(&mut bin, &items, &options, 0, &SolverMonitor::none());
+        assert!(unpacked.is_empty());
+        assert_eq!(bin.get_packed_items_volume(), bin.get_volume());
+    }
+}
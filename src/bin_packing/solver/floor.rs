@@ -0,0 +1,395 @@
+/*
+    2D floor planning for pallets on a truck floor.
+    Pallets with the same footprint are stacked into columns, columns are placed in rows
+    across the width of the floor, rows follow each other along the depth from the front.
+    Loading meters are the floor length used by the load, the unit freight forwarders charge by.
+*/
+use crate::bin_packing::bin::Bin;
+use crate::bin_packing::item::Item;
+use crate::bin_packing::options::FloorPlanOptions;
+use crate::bin_packing::result::{FloorPlanResult, LoadingMeters, UnpackedItem, UnpackedReason};
+use crate::bin_packing::solver::balance;
+use std::collections::BTreeMap;
+
+// footprints closer than this are the same footprint
+const FOOTPRINT_TOLERANCE: f32 = 1e-3;
+
+// Pallets stacked on each other, bottom pallet first
+struct PalletColumn {
+    pallets: Vec<Item>,
+    width: f32,
+    depth: f32,
+    height: f32,
+}
+
+impl PalletColumn {
+    fn new(pallet: Item) -> PalletColumn {
+        PalletColumn {
+            width: pallet.width,
+            depth: pallet.depth,
+            height: pallet.height,
+            pallets: vec![pallet],
+        }
+    }
+
+    // Same footprint, in either direction on the floor
+    fn has_footprint(&self, pallet: &Item) -> bool {
+        let same = |a: f32, b: f32| (a - b).abs() <= FOOTPRINT_TOLERANCE;
+        (same(self.width, pallet.width) && same(self.depth, pallet.depth))
+            || (same(self.width, pallet.depth) && same(self.depth, pallet.width))
+    }
+
+    // Check if every pallet of the column carries the weight above it with the pallet added on top
+    fn can_carry(&self, pallet: &Item) -> bool {
+        let mut load_above = pallet.weight;
+        for below in self.pallets.iter().rev() {
+            if below
+                .get_top_load_limit()
+                .is_some_and(|limit| load_above > limit)
+            {
+                return false;
+            }
+            load_above += below.weight;
+        }
+        true
+    }
+}
+
+/*
+    Heavier pallets are placed first, so they end up at the bottom of the columns.
+    A pallet goes on top of the first column with its footprint that it fits onto, otherwise it starts a column.
+*/
+fn get_pallet_columns(pallets: Vec<Item>, bin: &Bin, stacking: bool) -> Vec<PalletColumn> {
+    let mut pallets = pallets;
+    pallets.sort_by(|a, b| b.weight.total_cmp(&a.weight));
+
+    let mut columns: Vec<PalletColumn> = Vec::new();
+    for pallet in pallets {
+        let column = columns.iter_mut().find(|column| {
+            stacking
+                && column.has_footprint(&pallet)
+                && column.height + pallet.height <= bin.height
+                && column.can_carry(&pallet)
+        });
+        match column {
+            Some(column) => {
+                column.height += pallet.height;
+                column.pallets.push(pallet);
+            }
+            None => columns.push(PalletColumn::new(pallet)),
+        }
+    }
+    columns
+}
+
+// Row of columns across the width of the floor
+struct FloorRow {
+    // position along the depth of the bin
+    position: f32,
+    depth: f32,
+    used_width: f32,
+}
+
+// Footprint orientations of a column on the floor: (rotation, width, depth)
+fn get_floor_orientations(column: &PalletColumn) -> [(&'static str, f32, f32); 2] {
+    [
+        ("RT_WHD", column.width, column.depth),
+        ("RT_DHW", column.depth, column.width),
+    ]
+}
+
+/*
+    Orientation for a column that opens a new row, the one placing the most columns per floor length
+    if the row is filled with columns like it, the shorter row on a tie
+*/
+fn get_new_row_orientation(
+    column: &PalletColumn,
+    bin: &Bin,
+    remaining_depth: f32,
+) -> Option<(&'static str, f32, f32)> {
+    let columns_per_length = |width: f32, depth: f32| (bin.width / width).floor() / depth;
+    get_floor_orientations(column)
+        .into_iter()
+        .filter(|&(_, width, depth)| width <= bin.width && depth <= remaining_depth)
+        .max_by(|a, b| {
+            columns_per_length(a.1, a.2)
+                .total_cmp(&columns_per_length(b.1, b.2))
+                .then(b.2.total_cmp(&a.2))
+        })
+}
+
+// Rotation of a pallet that gives it the footprint of its column, pallets may join a column turned by 90°
+fn get_pallet_rotation(pallet: &Item, floor_width: f32) -> &'static str {
+    match (pallet.width - floor_width).abs() <= FOOTPRINT_TOLERANCE {
+        true => "RT_WHD",
+        false => "RT_DHW",
+    }
+}
+
+/*
+    Places the pallets of a column at its floor position, stacked from the floor up.
+    The rotation is the one of the column footprint, every pallet is turned to match it.
+*/
+fn place_column(bin: &mut Bin, column: PalletColumn, rotation: &str, x: f32, z: f32) {
+    let [floor_width, _, _] = column.pallets[0].get_rotated_dimension(rotation);
+    let mut y = 0.0;
+    for mut pallet in column.pallets {
+        let rotation = get_pallet_rotation(&pallet, floor_width);
+        pallet.rotation = rotation.to_string();
+        pallet.position = [x, y, z];
+        let dimension = pallet.get_rotated_dimension(rotation);
+        bin.axle_loads =
+            balance::get_axle_loads_with_item(bin, &dimension, &pallet.position, pallet.weight);
+        y += pallet.height;
+        bin.packed_items.push(pallet);
+    }
+}
+
+/*
+    Loading meters of a packed bin.
+    The floor length is the depth up to the rear end of the last item.
+    The area loading meters spread the floor area of the items resting on the floor over the bin width,
+    which is how partial loads that leave part of the width free are charged.
+*/
+pub fn get_loading_meters(bin: &Bin, units_per_meter: f32) -> LoadingMeters {
+    let mut floor_length: f32 = 0.0;
+    let mut floor_area = 0.0;
+    for item in &bin.packed_items {
+        let dimension = item.get_rotated_dimension(&item.rotation);
+        floor_length = floor_length.max(item.position[2] + dimension[2]);
+        if item.position[1] <= FOOTPRINT_TOLERANCE {
+            floor_area += dimension[0] * dimension[2];
+        }
+    }
+    let area_length = if bin.width > 0.0 {
+        floor_area / bin.width
+    } else {
+        0.0
+    };
+    LoadingMeters {
+        floor_length,
+        loading_meters: floor_length / units_per_meter,
+        area_loading_meters: area_length / units_per_meter,
+    }
+}
+
+// Order name of an item, items are named "order@item@index"
+fn get_order_name(item: &Item) -> &str {
+    item.name.split('@').next().unwrap_or(&item.name)
+}
+
+/*
+    Area loading meters taken by every order. A column is shared by its pallets,
+    each pallet takes the column footprint divided by the number of pallets in the column.
+    The values can be given as a "loading_meters" resource of dimensionless orders.
+*/
+fn get_order_loading_meters(bin: &Bin, units_per_meter: f32) -> BTreeMap<String, f32> {
+    let mut order_loading_meters = BTreeMap::new();
+    if bin.width <= 0.0 {
+        return order_loading_meters;
+    }
+    // pallets of a column share its floor position
+    let mut column_sizes: BTreeMap<(u32, u32), f32> = BTreeMap::new();
+    let floor_position = |item: &Item| (item.position[0].to_bits(), item.position[2].to_bits());
+    for item in &bin.packed_items {
+        *column_sizes.entry(floor_position(item)).or_insert(0.0) += 1.0;
+    }
+    for item in &bin.packed_items {
+        let dimension = item.get_rotated_dimension(&item.rotation);
+        let share = dimension[0] * dimension[2] / column_sizes[&floor_position(item)];
+        *order_loading_meters
+            .entry(get_order_name(item).to_string())
+            .or_insert(0.0) += share / bin.width / units_per_meter;
+    }
+    order_loading_meters
+}
+
+/*
+    Places pallets onto the floor of the bin. Columns are sorted by their longest side
+    and placed first fit into the open rows, a column that fits no row opens a new row behind the last one.
+*/
+pub fn plan_pallet_floor(
+    mut bin: Bin,
+    pallets: Vec<Item>,
+    options: &FloorPlanOptions,
+) -> FloorPlanResult {
+    let mut unpacked_items = Vec::new();
+
+    // heaviest pallets first until the bin weight is reached
+    let mut pallets = pallets;
+    pallets.sort_by(|a, b| b.weight.total_cmp(&a.weight));
+    let mut total_weight = 0.0;
+    let mut loaded_pallets = Vec::new();
+    for pallet in pallets {
+        if pallet.height > bin.height {
+            unpacked_items.push(UnpackedItem::new(&pallet, UnpackedReason::NoFreePosition));
+        } else if total_weight + pallet.weight > bin.max_weight {
+            unpacked_items.push(UnpackedItem::new(&pallet, UnpackedReason::OverWeight));
+        } else {
+            total_weight += pallet.weight;
+            loaded_pallets.push(pallet);
+        }
+    }
+
+    let mut columns = get_pallet_columns(loaded_pallets, &bin, options.stacking);
+    columns.sort_by(|a, b| {
+        let longest_side = |column: &PalletColumn| column.width.max(column.depth);
+        longest_side(b)
+            .total_cmp(&longest_side(a))
+            .then((b.width * b.depth).total_cmp(&(a.width * a.depth)))
+    });
+
+    let mut rows: Vec<FloorRow> = Vec::new();
+    for column in columns {
+        // first row with room across the width and enough depth, in the orientation filling most of the row depth
+        let placement = rows.iter_mut().find_map(|row| {
+            get_floor_orientations(&column)
+                .into_iter()
+                .filter(|&(_, width, depth)| {
+                    row.used_width + width <= bin.width && depth <= row.depth
+                })
+                .max_by(|a, b| a.2.total_cmp(&b.2).then(b.1.total_cmp(&a.1)))
+                .map(|orientation| (row, orientation))
+        });
+        if let Some((row, (rotation, width, _))) = placement {
+            let x = row.used_width;
+            row.used_width += width;
+            let z = row.position;
+            place_column(&mut bin, column, rotation, x, z);
+            continue;
+        }
+
+        let position = rows.last().map_or(0.0, |row| row.position + row.depth);
+        match get_new_row_orientation(&column, &bin, bin.depth - position) {
+            Some((rotation, width, depth)) => {
+                rows.push(FloorRow {
+                    position,
+                    depth,
+                    used_width: width,
+                });
+                place_column(&mut bin, column, rotation, 0.0, position);
+            }
+            None => {
+                for pallet in &column.pallets {
+                    unpacked_items.push(UnpackedItem::new(pallet, UnpackedReason::NoFreePosition));
+                }
+            }
+        }
+    }
+
+    bin.center_of_gravity = bin.get_center_of_gravity();
+    let order_loading_meters = match options.units_per_meter {
+        Some(units_per_meter) => {
+            bin.loading_meters = Some(get_loading_meters(&bin, units_per_meter));
+            get_order_loading_meters(&bin, units_per_meter)
+        }
+        None => BTreeMap::new(),
+    };
+    // columns are placed by floor space only, so the axle loads are reported instead of avoided
+    let mut violations = balance::get_axle_violations(&bin, 0.0);
+    violations.extend(balance::get_plan_violations(&bin));
+    FloorPlanResult {
+        violations,
+        bin,
+        unpacked_items,
+        order_loading_meters,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bin_packing::bin::RawBin;
+    use crate::bin_packing::item::RawItem;
+    use crate::bin_packing::options::PackingOptions;
+    use crate::bin_packing::order::RawOrder;
+    use crate::bin_packing::solver::verify::verify_packed_bin;
+
+    fn get_truck() -> Bin {
+        RawBin {
+            name: "truck".to_string(),
+            depth: 1360.0,
+            width: 245.0,
+            height: 270.0,
+            max_weight: 24000.0,
+            cog_envelope: None,
+            axles: None,
+        }
+        .convert_to_bin()
+    }
+
+    fn get_pallets(
+        name: &str,
+        quantity: i32,
+        width: f32,
+        depth: f32,
+        weight: f32,
+        stackable: bool,
+    ) -> Vec<Item> {
+        RawOrder {
+            name: name.to_string(),
+            items: vec![RawItem {
+                name: "pallet".to_string(),
+                quantity,
+                depth,
+                width,
+                height: 100.0,
+                weight,
+                max_top_load: None,
+            }],
+            orientable: None,
+            stackable: Some(stackable),
+        }
+        .create_order_from_raw_order()
+        .items
+    }
+
+    #[test]
+    fn pallets_turned_either_way_do_not_overlap() {
+        // the heavier crosswise pallets leave a column with one free place, a lengthwise pallet takes it
+        let pallets = [
+            get_pallets("crosswise", 21, 120.0, 80.0, 500.0, true),
+            get_pallets("lengthwise", 20, 80.0, 120.0, 400.0, true),
+            get_pallets("industrial", 6, 100.0, 120.0, 400.0, false),
+        ]
+        .concat();
+        let result = plan_pallet_floor(get_truck(), pallets, &FloorPlanOptions::default());
+        assert!(result.unpacked_items.is_empty());
+        assert!(result.violations.is_empty());
+
+        let items: Vec<Item> = result
+            .bin
+            .packed_items
+            .iter()
+            .map(|item| Item {
+                top_load: 0.0,
+                ..item.clone()
+            })
+            .collect();
+        let violations = verify_packed_bin(get_truck(), items, &PackingOptions::default());
+        assert_eq!(violations, Vec::new());
+    }
+
+    #[test]
+    fn euro_pallets_fill_the_truck_floor() {
+        // a 13.6 m trailer takes 34 euro pallets on the floor
+        let pallets = get_pallets("euro", 34, 80.0, 120.0, 400.0, false);
+        let options = FloorPlanOptions {
+            units_per_meter: Some(100.0),
+            stacking: false,
+        };
+        let result = plan_pallet_floor(get_truck(), pallets, &options);
+        assert!(result.unpacked_items.is_empty());
+        let loading_meters = result.bin.loading_meters.unwrap();
+        assert!((loading_meters.loading_meters - 13.6).abs() < 1e-3);
+    }
+
+    #[test]
+    fn loading_meters_need_units_per_meter() {
+        let pallets = get_pallets("euro", 4, 80.0, 120.0, 400.0, false);
+        let result = plan_pallet_floor(get_truck(), pallets, &FloorPlanOptions::default());
+        assert!(result.unpacked_items.is_empty());
+        assert!(result.bin.loading_meters.is_none());
+        assert!(result.order_loading_meters.is_empty());
+    }
+}
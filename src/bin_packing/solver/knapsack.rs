@@ -1,88 +1,914 @@
+/*
+    Knapsack over any number of float resources (weight, volume, pallet spaces, ...).
+    States only store a link to the state they extend, the selected orders are rebuilt once at the end.
+    After every item, states that are dominated (same conflict classes, using at least as much of every resource
+    and not more valuable than another state) are dropped, as no selection built on top of them
+    can beat the dominating state. States that can not beat the best known selection, even with the best of
+    the remaining items by a Lagrangian bound, are dropped as well.
+    Pruning does not bound the number of states: with the weight objective every distinct sum of float weights
+    is worth keeping, so the states can double with every order. Above a state limit only the most valuable
+    states are kept and the selection is no longer proven best.
+*/
+use crate::bin_packing::options::DimensionLessObjective;
 use crate::bin_packing::order::DimensionLessOrder;
-use ordered_float::OrderedFloat;
-use std::collections::BTreeMap;
-
-// Knapsack function for bins without volume constraints
-pub fn knapsack_1d_float(items: &[DimensionLessOrder], capacity: f64) -> Vec<DimensionLessOrder> {
-    let mut dp: BTreeMap<OrderedFloat<f64>, (f64, Vec<DimensionLessOrder>)> = BTreeMap::new();
-    dp.insert(OrderedFloat(0.0), (0.0, Vec::new()));
-
-    for item in items {
-        let mut new_dp = dp.clone();
-        for (&w_ordered, &(val, ref packed_items)) in dp.iter() {
-            let w = w_ordered.into_inner();
-            let new_w = w + item.weight;
-            if new_w <= capacity {
-                let new_val = val + item.weight;
-                let new_w_ordered = OrderedFloat(new_w);
-                match new_dp.get(&new_w_ordered) {
-                    Some(&(existing_val, _)) if existing_val >= new_val => (),
-                    _ => {
-                        let mut new_packed_items = packed_items.clone();
-                        new_packed_items.push(item.clone());
-                        new_dp.insert(new_w_ordered, (new_val, new_packed_items));
-                    }
+use crate::bin_packing::solver::approximate::knapsack_nd_approximate;
+
+// steps of the multiplier search that bounds the states
+const RELAXATION_ITERATIONS: usize = 50;
+
+/*
+    Bit set of conflict classes, one bit per class.
+    Two items conflict if the classes of one intersect the conflicts of the other.
+*/
+pub type ClassSet = Vec<u64>;
+
+pub fn intersects(a: &[u64], b: &[u64]) -> bool {
+    a.iter().zip(b).any(|(x, y)| x & y != 0)
+}
+
+pub fn union_into(target: &mut [u64], other: &[u64]) {
+    for (x, y) in target.iter_mut().zip(other) {
+        *x |= y;
+    }
+}
+
+// Item offered to the knapsack, all orders of an item are selected together
+#[derive(Clone)]
+pub struct KnapsackItem {
+    pub orders: Vec<DimensionLessOrder>,
+    // resources used by all orders of the item, in the order of the capacities
+    pub usage: Vec<f64>,
+    // conflict classes of the orders
+    pub classes: ClassSet,
+    // classes the orders may not share a bin with
+    pub conflicts: ClassSet,
+}
+
+impl KnapsackItem {
+    pub fn get_objective_value(&self, objective: DimensionLessObjective) -> f64 {
+        self.orders
+            .iter()
+            .map(|o| o.get_objective_value(objective))
+            .sum()
+    }
+}
+
+// Reachable knapsack state, its resource usage is stored in StateTable::usages
+struct State {
+    value: f64,
+    // (index of the extended state, index of the added order), None for the empty selection
+    parent: Option<(usize, usize)>,
+}
+
+/*
+    States with their resource usage stored in one flat list, `dimensions` values per state,
+    and their conflict classes in another one, `words` values per state
+*/
+struct StateTable {
+    dimensions: usize,
+    words: usize,
+    states: Vec<State>,
+    usages: Vec<f64>,
+    classes: Vec<u64>,
+}
+
+impl StateTable {
+    fn new(dimensions: usize, initial_classes: &[u64]) -> StateTable {
+        StateTable {
+            dimensions,
+            words: initial_classes.len(),
+            states: vec![State {
+                value: 0.0,
+                parent: None,
+            }],
+            usages: vec![0.0; dimensions],
+            classes: initial_classes.to_vec(),
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.states.len()
+    }
+
+    fn usage(&self, s: usize) -> &[f64] {
+        &self.usages[s * self.dimensions..(s + 1) * self.dimensions]
+    }
+
+    fn classes(&self, s: usize) -> &[u64] {
+        &self.classes[s * self.words..(s + 1) * self.words]
+    }
+
+    fn push(&mut self, state: State, usage: &[f64], classes: &[u64]) {
+        self.states.push(state);
+        self.usages.extend_from_slice(usage);
+        self.classes.extend_from_slice(classes);
+    }
+
+    fn swap(&mut self, a: usize, b: usize) {
+        self.states.swap(a, b);
+        for d in 0..self.dimensions {
+            self.usages
+                .swap(a * self.dimensions + d, b * self.dimensions + d);
+        }
+        for w in 0..self.words {
+            self.classes.swap(a * self.words + w, b * self.words + w);
+        }
+    }
+
+    fn truncate(&mut self, len: usize) {
+        self.states.truncate(len);
+        self.usages.truncate(len * self.dimensions);
+        self.classes.truncate(len * self.words);
+    }
+}
+
+// Fenwick tree answering "k-th largest value among the entries with rank <= r"
+struct TopTree {
+    k: usize,
+    // the k largest values of every node, largest first
+    tree: Vec<f64>,
+    // the k largest values of a query
+    largest: Vec<f64>,
+}
+
+impl TopTree {
+    fn new(size: usize, k: usize) -> TopTree {
+        TopTree {
+            k,
+            tree: vec![f64::NEG_INFINITY; (size + 1) * k],
+            largest: vec![f64::NEG_INFINITY; k],
+        }
+    }
+
+    fn insert(values: &mut [f64], value: f64) {
+        let Some(position) = values.iter().position(|&v| v < value) else {
+            return;
+        };
+        values.copy_within(position..values.len() - 1, position + 1);
+        values[position] = value;
+    }
+
+    fn update(&mut self, rank: usize, value: f64) {
+        let mut i = rank + 1;
+        while i * self.k < self.tree.len() {
+            TopTree::insert(&mut self.tree[i * self.k..(i + 1) * self.k], value);
+            i += i & i.wrapping_neg();
+        }
+    }
+
+    fn query(&mut self, rank: usize) -> f64 {
+        let mut i = rank + 1;
+        self.largest.fill(f64::NEG_INFINITY);
+        while i > 0 {
+            for &value in &self.tree[i * self.k..(i + 1) * self.k] {
+                if value <= self.largest[self.k - 1] {
+                    break;
                 }
+                TopTree::insert(&mut self.largest, value);
             }
+            i -= i & i.wrapping_neg();
+        }
+        self.largest[self.k - 1]
+    }
+}
+
+fn compare_usage(a: &[f64], b: &[f64]) -> std::cmp::Ordering {
+    a.iter()
+        .zip(b)
+        .map(|(x, y)| x.total_cmp(y))
+        .find(|ordering| ordering.is_ne())
+        .unwrap_or(std::cmp::Ordering::Equal)
+}
+
+/*
+    Keeps the states that fewer than `k` other states with the same conflict classes dominate.
+    A state dominated by k others can not be part of the k best selections,
+    as each dominating state completed with the same items is at least as good.
+    States with other classes are never compared, a state that allows more items is worth keeping.
+    Within the same classes, states are visited by resource usage, so no later state dominates an earlier one.
+*/
+fn prune_dominated_states(table: &StateTable, candidates: Vec<usize>, k: usize) -> Vec<usize> {
+    let mut candidates = candidates;
+    candidates.sort_by(|&a, &b| {
+        table
+            .classes(a)
+            .cmp(table.classes(b))
+            .then(compare_usage(table.usage(a), table.usage(b)))
+            .then(table.states[b].value.total_cmp(&table.states[a].value))
+    });
+
+    let mut frontier = Vec::new();
+    for run in candidates.chunk_by(|&a, &b| table.classes(a) == table.classes(b)) {
+        if table.dimensions <= 2 {
+            prune_dominated_run_2d(table, run, k, &mut frontier);
+        } else {
+            prune_dominated_run(table, run, k, &mut frontier);
+        }
+    }
+    frontier
+}
+
+/*
+    Pushes the states of a sorted run that fewer than k kept states of the run dominate, with up to two resources.
+    A tree tells if k earlier states are at most as heavy, at most as bulky and at least as valuable.
+*/
+fn prune_dominated_run_2d(table: &StateTable, run: &[usize], k: usize, frontier: &mut Vec<usize>) {
+    // second resource, or none for a single resource knapsack
+    let second_usage = |s: usize| table.usage(s).get(1).copied().unwrap_or(0.0);
+
+    // rank of every state by its second resource
+    let mut ranks: Vec<f64> = run.iter().map(|&s| second_usage(s)).collect();
+    ranks.sort_by(|a, b| a.total_cmp(b));
+    ranks.dedup();
+
+    let mut tree = TopTree::new(ranks.len(), k);
+    for &s in run {
+        let rank = ranks.partition_point(|&v| v < second_usage(s));
+        if tree.query(rank) >= table.states[s].value {
+            continue;
         }
-        dp = new_dp;
+        tree.update(rank, table.states[s].value);
+        frontier.push(s);
     }
+}
 
-    // Find the best value
-    let best_packed_items = dp
+// Pushes the states of a sorted run that fewer than k kept states of the run dominate, compared one by one
+fn prune_dominated_run(table: &StateTable, run: &[usize], k: usize, frontier: &mut Vec<usize>) {
+    let first_kept = frontier.len();
+    for &s in run {
+        let dominating_states = frontier[first_kept..]
+            .iter()
+            .filter(|&&kept| {
+                table.states[kept].value >= table.states[s].value
+                    && table
+                        .usage(kept)
+                        .iter()
+                        .zip(table.usage(s))
+                        .all(|(kept_usage, usage)| kept_usage <= usage)
+            })
+            .take(k)
+            .count();
+        if dominating_states < k {
+            frontier.push(s);
+        }
+    }
+}
+
+// Items added by value while they fit and do not conflict
+fn get_greedy_selection(
+    items: &[KnapsackItem],
+    capacities: &[f64],
+    initial_classes: &[u64],
+    objective: DimensionLessObjective,
+) -> Vec<usize> {
+    let mut by_value: Vec<(f64, usize)> = items
         .iter()
-        .max_by(|a, b| a.1 .0.partial_cmp(&b.1 .0).unwrap())
-        .map(|(_, v)| v.1.clone())
-        .unwrap();
+        .enumerate()
+        .map(|(item_index, item)| (item.get_objective_value(objective), item_index))
+        .filter(|(value, _)| *value > 0.0)
+        .collect();
+    by_value.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+    let mut used = vec![0.0; capacities.len()];
+    let mut classes = initial_classes.to_vec();
+    let mut selected_items = Vec::new();
+    for (_, item_index) in by_value {
+        let item = &items[item_index];
+        let fits = (0..capacities.len()).all(|d| used[d] + item.usage[d] <= capacities[d]);
+        if fits && !intersects(&classes, &item.conflicts) {
+            for (used, usage) in used.iter_mut().zip(&item.usage) {
+                *used += usage;
+            }
+            union_into(&mut classes, &item.classes);
+            selected_items.push(item_index);
+        }
+    }
+    selected_items.sort_unstable();
+    selected_items
+}
 
-    best_packed_items
+fn get_items_value(
+    items: &[KnapsackItem],
+    selected_items: &[usize],
+    objective: DimensionLessObjective,
+) -> f64 {
+    selected_items
+        .iter()
+        .map(|&item_index| items[item_index].get_objective_value(objective))
+        .sum()
+}
+
+/*
+    Most a state can still gain from the items after it. Either all of their positive values,
+    or a Lagrangian relaxation: every unit of capacity left is worth its multiplier,
+    and the remaining items are worth what their value exceeds their usage weighed by the multipliers.
+    Any multipliers give a bound, the ones of the approximation give the tightest bound it found.
+*/
+struct RemainingBound {
+    capacities: Vec<f64>,
+    multipliers: Vec<f64>,
+    // from an item index on, sums over the remaining items
+    remaining_values: Vec<f64>,
+    remaining_gains: Vec<f64>,
+}
+
+impl RemainingBound {
+    fn new(
+        items: &[KnapsackItem],
+        capacities: &[f64],
+        objective: DimensionLessObjective,
+        multipliers: Vec<f64>,
+    ) -> RemainingBound {
+        let mut remaining_values = vec![0.0; items.len() + 1];
+        let mut remaining_gains = vec![0.0; items.len() + 1];
+        for (item_index, item) in items.iter().enumerate().rev() {
+            let value = item.get_objective_value(objective);
+            let weighed_usage: f64 = item
+                .usage
+                .iter()
+                .zip(&multipliers)
+                .map(|(u, m)| u * m)
+                .sum();
+            remaining_values[item_index] = remaining_values[item_index + 1] + value.max(0.0);
+            remaining_gains[item_index] =
+                remaining_gains[item_index + 1] + (value - weighed_usage).max(0.0);
+        }
+        RemainingBound {
+            capacities: capacities.to_vec(),
+            multipliers,
+            remaining_values,
+            remaining_gains,
+        }
+    }
+
+    // Highest value a state reaches with the items from next_item on
+    fn get_upper_bound(&self, table: &StateTable, s: usize, next_item: usize) -> f64 {
+        let capacity_value: f64 = self
+            .capacities
+            .iter()
+            .zip(table.usage(s))
+            .zip(&self.multipliers)
+            .map(|((capacity, usage), multiplier)| (capacity - usage) * multiplier)
+            .sum();
+        let gain =
+            self.remaining_values[next_item].min(capacity_value + self.remaining_gains[next_item]);
+        table.states[s].value + gain
+    }
 }
 
-// Knapsack function for bins with volume constraints
-pub fn knapsack_2d_float(
-    items: &[DimensionLessOrder],
-    capacity_weight: f64,
-    capacity_volume: f64,
+/*
+    Drops the states that can not reach the value of the k-th best state, even with the best of the remaining items.
+    Such a state is beaten by k other selections, so it is not one of the k best.
+    lower_bound is a value some selection is known to reach, it stands in for the best state.
+*/
+fn prune_bounded_states(
+    table: &StateTable,
+    kept: &mut Vec<usize>,
+    k: usize,
+    bound: &RemainingBound,
+    next_item: usize,
+    lower_bound: f64,
+) {
+    let mut values: Vec<f64> = kept.iter().map(|&s| table.states[s].value).collect();
+    let kth_best_value = match (k, values.len() >= k) {
+        (1, _) => values.iter().copied().fold(lower_bound, f64::max),
+        (_, true) => {
+            *values
+                .select_nth_unstable_by(k - 1, |a, b| b.total_cmp(a))
+                .1
+        }
+        (_, false) => return,
+    };
+    // allowance for the rounding of the summed values
+    let threshold = kth_best_value - 1e-9 * kth_best_value.abs();
+    kept.retain(|&s| bound.get_upper_bound(table, s, next_item) >= threshold);
+}
+
+/*
+    How states are dropped after every item: the states that can be part of the k best selections are kept,
+    above max_states states the most valuable ones, or none if the run stops at the limit
+*/
+struct Pruning {
+    k: usize,
+    max_states: usize,
+    stop_at_limit: bool,
+}
+
+// Reachable states after the last item
+struct KnapsackRun {
+    table: StateTable,
+    frontier: Vec<usize>,
+    // states had to be dropped for the state limit
+    truncated: bool,
+    // items selected before the run, the states leading to them may have been dropped for the limit
+    known_selection: Vec<usize>,
+}
+
+/*
+    Builds the states of a knapsack over any number of resources.
+    initial_classes are the conflict classes already in the bin, items conflicting with them are never selected.
+*/
+fn run_knapsack(
+    items: &[KnapsackItem],
+    capacities: &[f64],
+    initial_classes: &[u64],
+    objective: DimensionLessObjective,
+    pruning: Pruning,
+) -> KnapsackRun {
+    let mut table = StateTable::new(capacities.len(), initial_classes);
+    let mut frontier: Vec<usize> = vec![0];
+    let mut truncated = false;
+    let mut new_usage = vec![0.0; capacities.len()];
+    let mut new_classes = vec![0; initial_classes.len()];
+    // the approximation bounds what the states can still gain and gives a selection to beat
+    let relaxation = knapsack_nd_approximate(
+        items,
+        capacities,
+        initial_classes,
+        objective,
+        RELAXATION_ITERATIONS,
+    );
+    let greedy_selection = get_greedy_selection(items, capacities, initial_classes, objective);
+    let known_selection = match get_items_value(items, &greedy_selection, objective)
+        > get_items_value(items, &relaxation.items, objective)
+    {
+        true => greedy_selection,
+        false => relaxation.items,
+    };
+    let lower_bound = match pruning.k {
+        1 => get_items_value(items, &known_selection, objective),
+        _ => f64::NEG_INFINITY,
+    };
+    let remaining_bound = RemainingBound::new(items, capacities, objective, relaxation.multipliers);
+
+    for (item_index, item) in items.iter().enumerate() {
+        let item_value = item.get_objective_value(objective);
+        let first_new_state = table.len();
+
+        for &s in &frontier {
+            if intersects(table.classes(s), &item.conflicts) {
+                continue;
+            }
+            let mut fits = true;
+            for d in 0..capacities.len() {
+                new_usage[d] = table.usage(s)[d] + item.usage[d];
+                fits &= new_usage[d] <= capacities[d];
+            }
+            if fits {
+                new_classes.copy_from_slice(table.classes(s));
+                union_into(&mut new_classes, &item.classes);
+                let state = State {
+                    value: table.states[s].value + item_value,
+                    parent: Some((s, item_index)),
+                };
+                table.push(state, &new_usage, &new_classes);
+            }
+        }
+
+        let candidates: Vec<usize> = frontier
+            .iter()
+            .copied()
+            .chain(first_new_state..table.len())
+            .collect();
+        let Pruning {
+            k,
+            max_states,
+            stop_at_limit,
+        } = pruning;
+        let mut kept = prune_dominated_states(&table, candidates, k);
+        prune_bounded_states(
+            &table,
+            &mut kept,
+            k,
+            &remaining_bound,
+            item_index + 1,
+            lower_bound,
+        );
+        if kept.len() > max_states && stop_at_limit {
+            return KnapsackRun {
+                table,
+                frontier: Vec::new(),
+                truncated: true,
+                known_selection: Vec::new(),
+            };
+        }
+        if kept.len() > max_states {
+            // keeps the order of the states, the next item then only merges sorted runs
+            let max_states = max_states.max(1);
+            let mut best_states = kept.clone();
+            let (_, &mut last_kept, _) = best_states
+                .select_nth_unstable_by(max_states - 1, |&a, &b| compare_states(&table, a, b));
+            kept.retain(|&s| compare_states(&table, s, last_kept).is_le());
+            kept.truncate(max_states);
+            truncated = true;
+        }
+        frontier = kept;
+
+        // new states that did not survive are never linked to, move the survivors down and drop the rest.
+        // The frontier keeps its order, the states of the next item are then added in the same order
+        let mut survivors: Vec<usize> = frontier
+            .iter()
+            .copied()
+            .filter(|&s| s >= first_new_state)
+            .collect();
+        survivors.sort_unstable();
+        let mut moved_to = vec![0; table.len() - first_new_state];
+        for (next, &s) in (first_new_state..).zip(&survivors) {
+            table.swap(s, next);
+            moved_to[s - first_new_state] = next;
+        }
+        table.truncate(first_new_state + survivors.len());
+        for s in frontier.iter_mut().filter(|s| **s >= first_new_state) {
+            *s = moved_to[*s - first_new_state];
+        }
+    }
+
+    KnapsackRun {
+        table,
+        frontier,
+        truncated,
+        known_selection,
+    }
+}
+
+// Follow the links of a state back to the empty selection, returns the selected items
+fn get_selected_items(table: &StateTable, state: usize) -> Vec<usize> {
+    let mut selected_items = Vec::new();
+    let mut current = state;
+    while let Some((parent, item_index)) = table.states[current].parent {
+        selected_items.push(item_index);
+        current = parent;
+    }
+    selected_items.reverse();
+    selected_items
+}
+
+fn get_selected_orders(
+    items: &[KnapsackItem],
+    selected_items: &[usize],
 ) -> Vec<DimensionLessOrder> {
-    let mut dp: BTreeMap<(OrderedFloat<f64>, OrderedFloat<f64>), (f64, Vec<DimensionLessOrder>)> =
-        BTreeMap::new();
-    dp.insert((OrderedFloat(0.0), OrderedFloat(0.0)), (0.0, Vec::new()));
-
-    for item in items {
-        let item_weight = item.weight;
-        let item_volume = item.volume.unwrap_or(0.0);
-
-        let mut new_dp = dp.clone();
-        for (&(w_ordered, v_ordered), &(val, ref packed_items)) in dp.iter() {
-            let w = w_ordered.into_inner();
-            let v = v_ordered.into_inner();
-
-            let new_w = w + item_weight;
-            let new_v = v + item_volume;
-
-            if new_w <= capacity_weight && new_v <= capacity_volume {
-                let new_val = val + item_weight;
-                let key = (OrderedFloat(new_w), OrderedFloat(new_v));
-
-                match new_dp.get(&key) {
-                    Some(&(existing_val, _)) if existing_val >= new_val => (),
-                    _ => {
-                        let mut new_packed_items = packed_items.clone();
-                        new_packed_items.push(item.clone());
-                        new_dp.insert(key, (new_val, new_packed_items));
-                    }
+    selected_items
+        .iter()
+        .flat_map(|&item_index| items[item_index].orders.clone())
+        .collect()
+}
+
+// Selections found by a knapsack run
+pub struct KnapsackSelections {
+    pub selections: Vec<Vec<DimensionLessOrder>>,
+    // false if states were dropped for the state limit, better selections may then be missing
+    pub complete: bool,
+}
+
+// What the knapsack does once more states are worth keeping than the limit
+#[derive(Clone, Copy, Debug)]
+pub enum StateLimit {
+    // give up, the selections are empty and incomplete
+    Stop(usize),
+    // keep the most valuable states and go on with them
+    KeepBest(usize),
+}
+
+/*
+    Best selection of items, the selection using less of the resources wins a tie.
+    Proven best only if the selections are complete, i.e. the state limit was never reached.
+*/
+pub fn knapsack_nd_float(
+    items: &[KnapsackItem],
+    capacities: &[f64],
+    initial_classes: &[u64],
+    objective: DimensionLessObjective,
+    limit: StateLimit,
+) -> KnapsackSelections {
+    let (max_states, stop_at_limit) = match limit {
+        StateLimit::Stop(max_states) => (max_states, true),
+        StateLimit::KeepBest(max_states) => (max_states, false),
+    };
+    let pruning = Pruning {
+        k: 1,
+        max_states,
+        stop_at_limit,
+    };
+    let run = run_knapsack(items, capacities, initial_classes, objective, pruning);
+    get_best_selections(run, items, objective, 1)
+}
+
+// Order of states by value, best first, then by resource usage
+fn compare_states(table: &StateTable, a: usize, b: usize) -> std::cmp::Ordering {
+    table.states[b]
+        .value
+        .total_cmp(&table.states[a].value)
+        .then(compare_usage(table.usage(a), table.usage(b)))
+}
+
+fn get_best_states(table: &StateTable, frontier: Vec<usize>) -> Vec<usize> {
+    let mut best_states = frontier;
+    best_states.sort_by(|&a, &b| compare_states(table, a, b));
+    best_states
+}
+
+// The k best selected items, best first
+fn get_best_items(
+    run: KnapsackRun,
+    items: &[KnapsackItem],
+    objective: DimensionLessObjective,
+    k: usize,
+) -> Vec<Vec<usize>> {
+    let best_items: Vec<Vec<usize>> = get_best_states(&run.table, run.frontier)
+        .into_iter()
+        .take(k)
+        .map(|s| get_selected_items(&run.table, s))
+        .collect();
+    // the states are only pruned against the known selection if one state is the best one
+    let known_value = get_items_value(items, &run.known_selection, objective);
+    let best_value = best_items
+        .first()
+        .map(|selected_items| get_items_value(items, selected_items, objective));
+    match k == 1
+        && !run.known_selection.is_empty()
+        && best_value.is_none_or(|value| known_value > value)
+    {
+        true => vec![run.known_selection],
+        false => best_items,
+    }
+}
+
+fn get_best_selections(
+    run: KnapsackRun,
+    items: &[KnapsackItem],
+    objective: DimensionLessObjective,
+    k: usize,
+) -> KnapsackSelections {
+    let complete = !run.truncated;
+    KnapsackSelections {
+        selections: get_best_items(run, items, objective, k)
+            .iter()
+            .map(|selected_items| get_selected_orders(items, selected_items))
+            .collect(),
+        complete,
+    }
+}
+
+/*
+    The k best distinct selections of items, best first.
+    Between selections of the same value, the one using less of the resources comes first.
+*/
+pub fn knapsack_nd_top_k(
+    items: &[KnapsackItem],
+    capacities: &[f64],
+    initial_classes: &[u64],
+    objective: DimensionLessObjective,
+    k: usize,
+    max_states: usize,
+) -> KnapsackSelections {
+    let pruning = Pruning {
+        k: k.max(1),
+        max_states,
+        stop_at_limit: false,
+    };
+    let run = run_knapsack(items, capacities, initial_classes, objective, pruning);
+    get_best_selections(run, items, objective, k)
+}
+
+/*
+    Selections no other selection beats on both of the first two resources (usually weight and volume),
+    i.e. no other selection is at least as heavy while using at most as much volume.
+    The front is ordered from the heaviest to the least bulky selection.
+    It is swept by volume: the heaviest selection within each of `count` volume limits spread evenly
+    up to the capacity, then the selections another one beats are dropped.
+    With front_dimensions 1 the front is the heaviest selection.
+*/
+pub fn knapsack_nd_pareto(
+    items: &[KnapsackItem],
+    capacities: &[f64],
+    initial_classes: &[u64],
+    front_dimensions: usize,
+    count: usize,
+    max_states: usize,
+) -> KnapsackSelections {
+    let objective = DimensionLessObjective::Weight;
+    let levels = match front_dimensions {
+        0 | 1 => 1,
+        _ => count.max(1),
+    };
+    let get_usage = |selected_items: &[usize], d: usize| -> f64 {
+        selected_items
+            .iter()
+            .map(|&item_index| items[item_index].usage.get(d).copied().unwrap_or(0.0))
+            .sum()
+    };
+
+    let mut complete = true;
+    let mut points = Vec::new();
+    for level in 1..=levels {
+        let mut level_capacities = capacities.to_vec();
+        if levels > 1 {
+            level_capacities[1] = capacities[1] * level as f64 / levels as f64;
+        }
+        let pruning = Pruning {
+            k: 1,
+            max_states,
+            stop_at_limit: false,
+        };
+        let run = run_knapsack(
+            items,
+            &level_capacities,
+            initial_classes,
+            objective,
+            pruning,
+        );
+        complete &= !run.truncated;
+        for selected_items in get_best_items(run, items, objective, 1) {
+            let second_usage = match front_dimensions {
+                0 | 1 => 0.0,
+                _ => get_usage(&selected_items, 1),
+            };
+            points.push((get_usage(&selected_items, 0), second_usage, selected_items));
+        }
+    }
+
+    // heaviest first, a selection is on the front if it is less bulky than every heavier one
+    points.sort_by(|a, b| b.0.total_cmp(&a.0).then(a.1.total_cmp(&b.1)));
+    let mut selections = Vec::new();
+    let mut min_second_usage = f64::INFINITY;
+    for (_, second_usage, selected_items) in points {
+        if second_usage < min_second_usage {
+            min_second_usage = second_usage;
+            selections.push(get_selected_orders(items, &selected_items));
+        }
+    }
+
+    KnapsackSelections {
+        selections,
+        complete,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bin_packing::solver::random::Random;
+    use std::collections::BTreeMap;
+
+    // Items with random weight, volume and value, each its own order
+    fn get_random_items(random: &mut Random, count: usize) -> Vec<KnapsackItem> {
+        (0..count)
+            .map(|index| {
+                let order = DimensionLessOrder {
+                    id: index.to_string(),
+                    weight: (1.0 + random.next_f32() * 99.0) as f64,
+                    volume: Some((1.0 + random.next_f32() * 9.0) as f64),
+                    value: Some((random.next_f32() * 50.0) as f64),
+                    resources: BTreeMap::new(),
+                    group: None,
+                    category: None,
+                };
+                KnapsackItem {
+                    usage: vec![order.weight, order.volume.unwrap_or(0.0)],
+                    orders: vec![order],
+                    classes: Vec::new(),
+                    conflicts: Vec::new(),
                 }
+            })
+            .collect()
+    }
+
+    // Values of every selection that fits the capacities, best first
+    fn get_feasible_values(
+        items: &[KnapsackItem],
+        capacities: &[f64],
+        objective: DimensionLessObjective,
+    ) -> Vec<f64> {
+        let mut values: Vec<f64> = (0..1usize << items.len())
+            .filter_map(|mask| {
+                let selected: Vec<&KnapsackItem> = (0..items.len())
+                    .filter(|index| mask >> index & 1 == 1)
+                    .map(|index| &items[index])
+                    .collect();
+                let fits = (0..capacities.len()).all(|d| {
+                    selected.iter().map(|item| item.usage[d]).sum::<f64>() <= capacities[d]
+                });
+                fits.then(|| {
+                    selected
+                        .iter()
+                        .map(|item| item.get_objective_value(objective))
+                        .sum()
+                })
+            })
+            .collect();
+        values.sort_by(|a, b| b.total_cmp(a));
+        values
+    }
+
+    fn get_value(orders: &[DimensionLessOrder], objective: DimensionLessObjective) -> f64 {
+        orders
+            .iter()
+            .map(|o| o.get_objective_value(objective))
+            .sum()
+    }
+
+    #[test]
+    fn knapsack_finds_the_best_selection() {
+        let mut random = Random::new(1);
+        for objective in [
+            DimensionLessObjective::Weight,
+            DimensionLessObjective::Value,
+        ] {
+            for _ in 0..200 {
+                let items = get_random_items(&mut random, 10);
+                let capacities = [
+                    (50.0 + random.next_f32() * 300.0) as f64,
+                    (5.0 + random.next_f32() * 30.0) as f64,
+                ];
+                let result = knapsack_nd_float(
+                    &items,
+                    &capacities,
+                    &[],
+                    objective,
+                    StateLimit::KeepBest(100_000),
+                );
+                assert!(result.complete);
+                let best = get_feasible_values(&items, &capacities, objective)[0];
+                let value = get_value(&result.selections[0], objective);
+                assert!((value - best).abs() < 1e-6, "{value} != {best}");
             }
         }
-        dp = new_dp;
     }
 
-    // Find the best value
-    let best_packed_items = dp
-        .iter()
-        .max_by(|a, b| a.1 .0.partial_cmp(&b.1 .0).unwrap())
-        .map(|(_, v)| v.1.clone())
-        .unwrap();
+    #[test]
+    fn top_k_lists_the_best_selections_first() {
+        let mut random = Random::new(2);
+        let objective = DimensionLessObjective::Value;
+        for _ in 0..100 {
+            let items = get_random_items(&mut random, 8);
+            let capacities = [150.0, 20.0];
+            let result = knapsack_nd_top_k(&items, &capacities, &[], objective, 5, 100_000);
+            assert!(result.complete);
+            let feasible_values = get_feasible_values(&items, &capacities, objective);
+            for (selection, best) in result.selections.iter().zip(feasible_values) {
+                let value = get_value(selection, objective);
+                assert!((value - best).abs() < 1e-6, "{value} != {best}");
+            }
+        }
+    }
 
-    best_packed_items
+    #[test]
+    fn conflicting_items_are_never_selected_together() {
+        let mut random = Random::new(3);
+        let mut items = get_random_items(&mut random, 2);
+        // first item is class 0, the second one conflicts with it
+        items[0].classes = vec![1];
+        items[0].conflicts = vec![0];
+        items[1].classes = vec![0];
+        items[1].conflicts = vec![1];
+        let result = knapsack_nd_float(
+            &items,
+            &[1000.0, 1000.0],
+            &[0],
+            DimensionLessObjective::Count,
+            StateLimit::KeepBest(100),
+        );
+        assert_eq!(result.selections[0].len(), 1);
+    }
+
+    #[test]
+    fn stopped_run_returns_no_selection() {
+        let mut random = Random::new(4);
+        // float weights against a weight limit only, every sum of weights is a state of its own
+        let items = get_random_items(&mut random, 40);
+        let capacities = [1000.0, 1000.0];
+        let objective = DimensionLessObjective::Weight;
+        let stopped = knapsack_nd_float(&items, &capacities, &[], objective, StateLimit::Stop(10));
+        assert!(!stopped.complete && stopped.selections.is_empty());
+        let kept = knapsack_nd_float(
+            &items,
+            &capacities,
+            &[],
+            objective,
+            StateLimit::KeepBest(10),
+        );
+        assert!(!kept.complete && kept.selections.len() == 1);
+    }
+
+    #[test]
+    fn approximation_is_bounded_around_the_best_selection() {
+        let mut random = Random::new(5);
+        let objective = DimensionLessObjective::Value;
+        for _ in 0..200 {
+            let items = get_random_items(&mut random, 10);
+            let capacities = [
+                (50.0 + random.next_f32() * 300.0) as f64,
+                (5.0 + random.next_f32() * 30.0) as f64,
+            ];
+            let approximation = knapsack_nd_approximate(&items, &capacities, &[], objective, 100);
+            let best = get_feasible_values(&items, &capacities, objective)[0];
+            let value = get_value(&approximation.orders, objective);
+            assert!(value <= best + 1e-6, "{value} > {best}");
+            assert!(approximation.upper_bound >= best - 1e-6);
+            for (d, capacity) in capacities.iter().enumerate() {
+                let used: f64 = approximation.items.iter().map(|&i| items[i].usage[d]).sum();
+                assert!(used <= *capacity);
+            }
+        }
+    }
 }
@@ -1,13 +1,59 @@
 /*
-    This solver is modified to be used with the wasm_bindgen library.
-    Modified functions:
-    - get_smallest_fitting_bin_for_item_vector
+    This solver works on plain rust types and runs natively as well as behind the wasm bindings.
+    Progress is reported to the monitor of the run, which is also polled for cancellation.
 */
+pub mod approximate;
+pub mod assignment;
+pub mod balance;
+pub mod clock;
+pub mod dimensionless;
+pub mod extreme_points;
+pub mod floor;
 pub mod knapsack;
+pub mod placement;
+pub mod random;
+pub mod rules;
+pub mod search;
+pub mod support;
+pub mod verify;
 use crate::bin_packing::bin::Bin;
 use crate::bin_packing::item::Item;
-use js_sys::Function;
-use wasm_bindgen::JsValue;
+use crate::bin_packing::options::{PackingOptions, PlacementEngine, PlacementPolicy};
+use crate::bin_packing::progress::{ProgressEvent, SolverMonitor};
+use crate::bin_packing::result::{
+    MultiBinPackingResult, PackingResult, UnpackedItem, UnpackedReason,
+};
+
+const ALL_ROTATIONS: [&str; 6] = ["RT_WHD", "RT_HWD", "RT_HDW", "RT_DHW", "RT_DWH", "RT_WDH"];
+// rotations that keep the height of the item vertical, turning it only about the vertical axis
+const UPRIGHT_ROTATIONS: [&str; 2] = ["RT_WHD", "RT_DHW"];
+
+pub fn get_allowed_rotations(orientable: bool) -> &'static [&'static str] {
+    if orientable {
+        &ALL_ROTATIONS
+    } else {
+        &UPRIGHT_ROTATIONS
+    }
+}
+
+// Allowed rotations, the current rotation of the item is tried first
+fn get_rotation_order(item: &Item, orientable: bool) -> Vec<&'static str> {
+    let mut rotations = get_allowed_rotations(orientable).to_vec();
+    if let Some(index) = rotations.iter().position(|&rt| rt == item.rotation) {
+        rotations[..=index].rotate_right(1);
+    }
+    rotations
+}
+
+// Check if the item fits into the empty bin with any of the given rotations
+fn fits_bin_dimensions(bin: &Bin, item: &Item, rotations: &[&str]) -> bool {
+    rotations.iter().any(|rt| {
+        let item_dimension = item.get_rotated_dimension(rt);
+        item_dimension[0] <= bin.width
+            && item_dimension[1] <= bin.height
+            && item_dimension[2] <= bin.depth
+    })
+}
 
 // "Axis-Aligned Bounding Box" (AABB) collision detection method
 fn check_rectangle_intersection(
@@ -34,7 +80,7 @@ fn check_rectangle_intersection(
         && existing_max_y > new_min_y
 }
 
-fn check_intersection(
+pub fn check_intersection(
     existing_item: &Item,
     new_item_dimension: &[f32; 3],
     new_item_position: &[f32; 3],
@@ -44,9 +90,9 @@ fn check_intersection(
 
     if !check_rectangle_intersection(
         &existing_dimension,
-        &existing_position,
-        &new_item_dimension,
-        &new_item_position,
+        existing_position,
+        new_item_dimension,
+        new_item_position,
         0,
         1,
     ) {
@@ -54,9 +100,9 @@ fn check_intersection(
     }
     if !check_rectangle_intersection(
         &existing_dimension,
-        &existing_position,
-        &new_item_dimension,
-        &new_item_position,
+        existing_position,
+        new_item_dimension,
+        new_item_position,
         1,
         2,
     ) {
@@ -64,9 +110,9 @@ fn check_intersection(
     }
     if !check_rectangle_intersection(
         &existing_dimension,
-        &existing_position,
-        &new_item_dimension,
-        &new_item_position,
+        existing_position,
+        new_item_dimension,
+        new_item_position,
         0,
         2,
     ) {
@@ -75,47 +121,168 @@ fn check_intersection(
     true
 }
 
+// Check if the item fits at the pivot with the given rotation
+fn check_rotation_at_pivot(
+    bin: &Bin,
+    item: &Item,
+    pivot: &[f32; 3],
+    item_dimension: &[f32; 3],
+    options: &PackingOptions,
+) -> Result<(), UnpackedReason> {
+    // Check if item at pivot exceeds bin dimensions
+    if pivot[0] + item_dimension[0] > bin.width
+        || pivot[1] + item_dimension[1] > bin.height
+        || pivot[2] + item_dimension[2] > bin.depth
+    {
+        return Err(UnpackedReason::NoFreePosition);
+    }
+
+    // item at current rotation, doesn't exceed bin dimensions
+    // for each item in bin, check if there is an intersection
+    for item_in_bin in &bin.packed_items {
+        if check_intersection(item_in_bin, item_dimension, pivot) {
+            return Err(UnpackedReason::NoFreePosition);
+        }
+    }
+
+    // Check if enough of the item's base rests on the floor or on items below
+    let support_ratio = support::get_support_ratio(bin, item_dimension, pivot);
+    if !support::is_supported(support_ratio, options.min_support_ratio) {
+        return Err(UnpackedReason::Unsupported);
+    }
+
+    // Check if the items below can carry the weight of the item
+    let added_loads = support::get_added_top_loads(bin, item_dimension, pivot, item.weight);
+    if !support::check_top_loads(bin, &added_loads) {
+        return Err(UnpackedReason::TopLoadExceeded);
+    }
+
+    // Check if the axles of the bin can carry the weight of the item
+    let axle_loads = balance::get_axle_loads_with_item(bin, item_dimension, pivot, item.weight);
+    if !balance::check_axle_loads(bin, &axle_loads) {
+        return Err(UnpackedReason::AxleOverload);
+    }
+
+    Ok(())
+}
+
 fn check_item_in_bin_at_pivot(
     bin: &Bin,
     item: &Item,
     pivot: &[f32; 3],
     orientable: bool,
-) -> Option<String> {
-    let rotations = if orientable {
-        vec!["RT_WHD", "RT_HWD", "RT_HDW", "RT_DHW", "RT_DWH", "RT_WDH"]
-    } else {
-        vec!["RT_WHD", "RT_DWH"]
-    };
+    options: &PackingOptions,
+) -> Result<String, UnpackedReason> {
+    let mut reason = UnpackedReason::NoFreePosition;
 
-    for rt in rotations {
+    for rt in get_rotation_order(item, orientable) {
         let item_dimension = item.get_rotated_dimension(rt);
-
-        // Check if item at pivot exceeds bin dimensions, if yes then try next rotation
-        if pivot[0] + item_dimension[0] > bin.width
-            || pivot[1] + item_dimension[1] > bin.height
-            || pivot[2] + item_dimension[2] > bin.depth
-        {
-            continue;
+        match check_rotation_at_pivot(bin, item, pivot, &item_dimension, options) {
+            Ok(()) => return Ok(rt.to_string()),
+            Err(UnpackedReason::NoFreePosition) => continue,
+            Err(rotation_reason) => reason = rotation_reason,
         }
+    }
+    Err(reason)
+}
+
+// First open pivot that takes the item, with the first rotation that fits there
+fn find_first_fit(
+    bin: &Bin,
+    item: &Item,
+    open_pivots: &[[f32; 3]],
+    orientable: bool,
+    options: &PackingOptions,
+) -> Result<([f32; 3], String), UnpackedReason> {
+    let mut reason = UnpackedReason::NoFreePosition;
 
-        // item at current rotation, doesn't exceed bin dimensions
-        // for each item in bin, check if there is an intersection
+    for pivot in open_pivots {
+        match check_item_in_bin_at_pivot(bin, item, pivot, orientable, options) {
+            Ok(rotation_string) => return Ok((*pivot, rotation_string)),
+            Err(pivot_reason) => {
+                // keep the most specific reason over plain lack of space
+                if pivot_reason != UnpackedReason::NoFreePosition {
+                    reason = pivot_reason;
+                }
+            }
+        }
+    }
+    Err(reason)
+}
 
-        let mut intersection_failure = false;
+// Every open pivot and rotation that takes the item is scored, the lowest score wins
+fn find_best_fit(
+    bin: &Bin,
+    item: &Item,
+    open_pivots: &[[f32; 3]],
+    orientable: bool,
+    options: &PackingOptions,
+) -> Result<([f32; 3], String), UnpackedReason> {
+    let mut reason = UnpackedReason::NoFreePosition;
+    let mut best: Option<([f32; 3], [f32; 3], &str)> = None;
+    let rotations = get_rotation_order(item, orientable);
 
-        for item_in_bin in &bin.packed_items {
-            if check_intersection(&item_in_bin, &item_dimension, pivot) {
-                intersection_failure = true;
-                break; //exit this for loop
+    for pivot in open_pivots {
+        for &rt in &rotations {
+            let item_dimension = item.get_rotated_dimension(rt);
+            match check_rotation_at_pivot(bin, item, pivot, &item_dimension, options) {
+                Ok(()) => {
+                    let score = placement::get_placement_score(
+                        bin,
+                        options.placement_policy,
+                        pivot,
+                        &item_dimension,
+                    );
+                    if best.is_none_or(|(best_score, _, _)| score < best_score) {
+                        best = Some((score, *pivot, rt));
+                    }
+                }
+                Err(UnpackedReason::NoFreePosition) => {}
+                Err(rotation_reason) => reason = rotation_reason,
             }
         }
+    }
+
+    match best {
+        Some((_, pivot, rt)) => Ok((pivot, rt.to_string())),
+        None => Err(reason),
+    }
+}
+
+// Pivots at the three corners of the placed item, in addition to the remaining open pivots
+fn get_corner_pivots(
+    bin: &Bin,
+    open_pivots: &[[f32; 3]],
+    pivot: &[f32; 3],
+    item_dimension: &[f32; 3],
+    stackable: bool,
+) -> Vec<[f32; 3]> {
+    // Initialize new pivots
+    let mut new_pivots = Vec::new();
+
+    // Create new pivots based on the current pivot and item dimensions
+    let new_pivot_along_width = [pivot[0] + item_dimension[0], pivot[1], pivot[2]];
+    let new_pivot_along_depth = [pivot[0], pivot[1], pivot[2] + item_dimension[2]];
+    let new_pivot_along_height = [pivot[0], pivot[1] + item_dimension[1], pivot[2]];
+
+    // Check if the new pivots are within bin dimensions before adding them
+    if new_pivot_along_width[0] <= bin.width {
+        new_pivots.push(new_pivot_along_width);
+    }
+    if new_pivot_along_depth[2] <= bin.depth {
+        new_pivots.push(new_pivot_along_depth);
+    }
+    if stackable && new_pivot_along_height[1] <= bin.height {
+        new_pivots.push(new_pivot_along_height);
+    }
 
-        if !intersection_failure {
-            return Some(rt.to_string());
+    // Copy existing open pivots except the used one
+    for &existing_pivot in open_pivots {
+        if existing_pivot != *pivot {
+            new_pivots.push(existing_pivot);
         }
     }
-    // by default return false
-    return None;
+    new_pivots
 }
 
 pub fn pack_item_to_bin(
@@ -124,102 +291,303 @@ pub fn pack_item_to_bin(
     open_pivots: &[[f32; 3]],
     stackable: bool,
     orientable: bool,
-) -> Option<Vec<[f32; 3]>> {
-    for pivot in open_pivots {
-        // Check volume and weight constraints
-        if item.get_volume() + bin.get_packed_items_volume() > bin.get_volume()
-            || item.weight + bin.get_packed_items_weight() > bin.max_weight
-        {
-            // Item cannot be packed into this bin due to volume or weight constraints
-            return None;
-        }
+    options: &PackingOptions,
+) -> Result<Vec<[f32; 3]>, UnpackedReason> {
+    // Check volume and weight constraints
+    if item.weight + bin.get_packed_items_weight() > bin.max_weight {
+        return Err(UnpackedReason::OverWeight);
+    }
+    if item.get_volume() + bin.get_packed_items_volume() > bin.get_volume() {
+        return Err(UnpackedReason::OverVolume);
+    }
 
-        if let Some(rotation_string) = check_item_in_bin_at_pivot(bin, item, pivot, orientable) {
-            // Get the rotated dimensions using the rotation_string
-            let item_dimension = item.get_rotated_dimension(&rotation_string);
+    let placement = match options.placement_policy {
+        PlacementPolicy::FirstFit => find_first_fit(bin, item, open_pivots, orientable, options),
+        _ => find_best_fit(bin, item, open_pivots, orientable, options),
+    };
 
-            // Initialize new pivots
-            let mut new_pivots = Vec::new();
+    let (pivot, rotation_string) = match placement {
+        Ok(placement) => placement,
+        // Item cannot be packed into the bin at any pivot
+        Err(reason) => return Err(get_unplaced_reason(bin, item, orientable, reason)),
+    };
 
-            // Create new pivots based on the current pivot and item dimensions
-            let new_pivot_along_width = [pivot[0] + item_dimension[0], pivot[1], pivot[2]];
-            let new_pivot_along_depth = [pivot[0], pivot[1], pivot[2] + item_dimension[2]];
-            let new_pivot_along_height = [pivot[0], pivot[1] + item_dimension[1], pivot[2]];
+    // Get the rotated dimensions using the rotation_string
+    let item_dimension = item.get_rotated_dimension(&rotation_string);
 
-            // Check if the new pivots are within bin dimensions before adding them
-            if new_pivot_along_width[0] <= bin.width {
-                new_pivots.push(new_pivot_along_width);
-            }
-            if new_pivot_along_depth[2] <= bin.depth {
-                new_pivots.push(new_pivot_along_depth);
-            }
-            if stackable && new_pivot_along_height[1] <= bin.height {
-                new_pivots.push(new_pivot_along_height);
-            }
+    // Put the weight of the item on the items below
+    let added_loads = support::get_added_top_loads(bin, &item_dimension, &pivot, item.weight);
+    for (item_in_bin, added_load) in bin.packed_items.iter_mut().zip(added_loads) {
+        item_in_bin.top_load += added_load;
+    }
+    bin.axle_loads = balance::get_axle_loads_with_item(bin, &item_dimension, &pivot, item.weight);
 
-            // Copy existing open pivots except the used one
-            for &existing_pivot in open_pivots {
-                if existing_pivot != *pivot {
-                    new_pivots.push(existing_pivot);
-                }
-            }
+    // Now create packed_item and push it into bin.packed_items
+    let mut packed_item = item.clone();
+    packed_item.rotation = rotation_string;
+    packed_item.position = pivot;
+    bin.packed_items.push(packed_item);
 
-            // Now create packed_item and push it into bin.packed_items
-            let mut packed_item = item.clone();
-            packed_item.rotation = rotation_string;
-            packed_item.position = *pivot;
-            bin.packed_items.push(packed_item);
+    let new_pivots = match options.placement_engine {
+        PlacementEngine::Pivots => {
+            get_corner_pivots(bin, open_pivots, &pivot, &item_dimension, stackable)
+        }
+        PlacementEngine::ExtremePoints => {
+            extreme_points::get_extreme_points(bin, open_pivots, stackable)
+        }
+    };
+    Ok(new_pivots)
+}
 
-            return Some(new_pivots);
+// Tells apart a forbidden orientation from plain lack of space
+fn get_unplaced_reason(
+    bin: &Bin,
+    item: &Item,
+    orientable: bool,
+    reason: UnpackedReason,
+) -> UnpackedReason {
+    if reason != UnpackedReason::NoFreePosition {
+        return reason;
+    }
+    if !orientable
+        && !fits_bin_dimensions(bin, item, &UPRIGHT_ROTATIONS)
+        && fits_bin_dimensions(bin, item, &ALL_ROTATIONS)
+    {
+        // item would only fit the bin if it was tipped over
+        return UnpackedReason::OrientationForbidden;
+    }
+    UnpackedReason::NoFreePosition
+}
+
+/*
+    Packs items one by one into the bin, starting from an empty pivot list.
+    Returns the items that could not be placed together with the reason.
+*/
+pub fn pack_items_into_bin(
+    bin: &mut Bin,
+    sorted_item_list: &[Item],
+    options: &PackingOptions,
+    bin_index: usize,
+    monitor: &SolverMonitor,
+) -> Vec<(Item, UnpackedReason)> {
+    // Initialize open pivots
+    let mut open_pivots = vec![[0.0, 0.0, 0.0]];
+    let mut unpacked_items = Vec::new();
+
+    monitor.report(ProgressEvent::BinStarted {
+        bin_index,
+        bin_name: bin.name.clone(),
+    });
+    for (item_index, item) in sorted_item_list.iter().enumerate() {
+        if monitor.is_cancelled() {
+            unpacked_items.push((item.clone(), UnpackedReason::Cancelled));
+            continue;
+        }
+        match pack_item_to_bin(
+            bin,
+            item,
+            &open_pivots,
+            item.stackable.unwrap_or(true),  // default to stackable
+            item.orientable.unwrap_or(true), // default to orientable
+            options,
+        ) {
+            Ok(new_pivots) => {
+                open_pivots = new_pivots;
+                monitor.report(ProgressEvent::ItemPlaced {
+                    bin_index,
+                    item_index,
+                    item_name: item.name.clone(),
+                });
+            }
+            Err(reason) => unpacked_items.push((item.clone(), reason)),
         }
     }
-    None // Item cannot be packed into the bin at any pivot
+    bin.center_of_gravity = bin.get_center_of_gravity();
+    if let Some(units_per_meter) = options.units_per_meter {
+        bin.loading_meters = Some(floor::get_loading_meters(bin, units_per_meter));
+    }
+    unpacked_items
+}
+
+fn to_unpacked_item_list(unpacked_items: &[(Item, UnpackedReason)]) -> Vec<UnpackedItem> {
+    unpacked_items
+        .iter()
+        .map(|(item, reason)| UnpackedItem::new(item, *reason))
+        .collect()
 }
 
+/*
+    Returns the smallest bin that holds all items.
+    If there is no such bin, the result holds the fullest bin achieved and the items left out.
+*/
 pub fn get_smallest_fitting_bin_for_item_vector(
-    sorted_item_list: &Vec<Item>,
-    sorted_bin_list: &mut Vec<Bin>,
+    sorted_item_list: &[Item],
+    sorted_bin_list: &mut [Bin],
     total_order_weight: f32,
     total_order_volume: f32,
-    js_update_function: &Function,
-) -> Option<Bin> {
+    options: &PackingOptions,
+    monitor: &SolverMonitor,
+) -> PackingResult {
+    let mut fullest: Option<PackingResult> = None;
+    // bins failing the basic W/V tests can not hold all items,
+    // they are only tried if every bin fails them, to show the fullest partial packing
+    let passes_basic_tests =
+        |bin: &Bin| bin.max_weight >= total_order_weight && bin.get_volume() >= total_order_volume;
+    let any_bin_passes = sorted_bin_list.iter().any(passes_basic_tests);
+
     for (bin_index, bin) in sorted_bin_list.iter_mut().enumerate() {
+        if monitor.is_cancelled() {
+            break;
+        }
         // Reject bin if orders don't pass basic W/V tests
-        if bin.max_weight < total_order_weight || bin.get_volume() < total_order_volume {
+        if any_bin_passes && !passes_basic_tests(bin) {
             continue;
         }
-        // Initialize open pivots
-        let mut open_pivots = vec![[0.0, 0.0, 0.0]];
-
-        for (item_index, item) in sorted_item_list.iter().enumerate() {
-            if let Some(new_pivots) = pack_item_to_bin(
-                bin,
-                item,
-                &open_pivots,
-                match item.stackable {
-                    Some(s) => s,
-                    None => true, // default to stackable
-                },
-                match item.orientable {
-                    Some(o) => o,
-                    None => true, // default to orientable
-                },
-            ) {
-                open_pivots = new_pivots;
-                if item_index % 10 == 0 {
-                    let progress_string: String = format!("{}-{}", bin_index, item_index);
-                    // pass progress to js
-                    _ = js_update_function.call1(&JsValue::NULL, &JsValue::from(progress_string));
+
+        let unpacked_items =
+            pack_items_into_bin(bin, sorted_item_list, options, bin_index, monitor);
+        let result = PackingResult {
+            bin: Some(bin.clone()),
+            unpacked_items: to_unpacked_item_list(&unpacked_items),
+            violations: balance::get_plan_violations(bin),
+        };
+
+        // If all items are packed and the plan is valid
+        if result.is_complete() {
+            return result;
+        }
+
+        if fullest
+            .as_ref()
+            .is_none_or(|fullest| is_fuller(&result, fullest))
+        {
+            fullest = Some(result);
+        }
+    }
+
+    // bin list is empty
+    fullest.unwrap_or_else(|| PackingResult {
+        bin: None,
+        unpacked_items: sorted_item_list
+            .iter()
+            .map(|item| UnpackedItem::new(item, UnpackedReason::NoFreePosition))
+            .collect(),
+        violations: Vec::new(),
+    })
+}
+
+// Valid plans come first, then the packed volume
+fn is_fuller(result: &PackingResult, other: &PackingResult) -> bool {
+    let packed_volume = |result: &PackingResult| {
+        result
+            .bin
+            .as_ref()
+            .map_or(0.0, |bin| bin.get_packed_items_volume())
+    };
+    (result.violations.is_empty(), packed_volume(result))
+        > (other.violations.is_empty(), packed_volume(other))
+}
+
+/*
+    Packs the item list into as many bins as needed, opening bins from the bin catalog.
+    Every bin in the catalog may be used any number of times.
+    While the remaining items fit into a single bin, the smallest such bin closes the plan.
+    Otherwise the bin type that takes the largest packed volume is opened, which keeps the number of bins low.
+    Bins whose centre of gravity is outside their envelope are not opened.
+    Items that do not fit even into an empty bin are returned as unpacked items.
+*/
+pub fn pack_item_vector_into_multiple_bins(
+    sorted_item_list: &[Item],
+    sorted_bin_list: &[Bin],
+    options: &PackingOptions,
+    monitor: &SolverMonitor,
+) -> MultiBinPackingResult {
+    let mut packed_bins: Vec<Bin> = Vec::new();
+    let mut remaining_items: Vec<Item> = sorted_item_list.to_vec();
+
+    while !remaining_items.is_empty() {
+        if monitor.is_cancelled() {
+            return MultiBinPackingResult {
+                bins: packed_bins,
+                unpacked_items: remaining_items
+                    .iter()
+                    .map(|item| UnpackedItem::new(item, UnpackedReason::Cancelled))
+                    .collect(),
+            };
+        }
+        let remaining_weight: f32 = remaining_items.iter().map(|item| item.weight).sum();
+        let remaining_volume: f32 = remaining_items.iter().map(|item| item.get_volume()).sum();
+
+        // Close the plan with the smallest bin that takes all remaining items
+        let result = get_smallest_fitting_bin_for_item_vector(
+            &remaining_items,
+            &mut sorted_bin_list.to_vec(),
+            remaining_weight,
+            remaining_volume,
+            options,
+            monitor,
+        );
+        if result.is_complete() {
+            packed_bins.extend(result.bin);
+            break;
+        }
+
+        // Otherwise open the bin that takes the largest share of the remaining items
+        let mut best: Option<(Bin, Vec<(Item, UnpackedReason)>)> = None;
+        // reasons reported if no bin takes any of the remaining items
+        let mut rejected: Vec<(Item, UnpackedReason)> = Vec::new();
+        for template in sorted_bin_list {
+            let mut bin = template.clone();
+            let unpacked_items = pack_items_into_bin(
+                &mut bin,
+                &remaining_items,
+                options,
+                packed_bins.len(),
+                monitor,
+            );
+            if bin.packed_items.is_empty() {
+                rejected = unpacked_items;
+                continue;
+            }
+            if !bin.is_balanced() {
+                // the bin takes these items, but not with a valid centre of gravity
+                rejected = bin
+                    .packed_items
+                    .iter()
+                    .map(|item| (item.clone(), UnpackedReason::Unbalanced))
+                    .chain(unpacked_items)
+                    .collect();
+                continue;
+            }
+            let is_better = match &best {
+                Some((best_bin, _)) => {
+                    bin.get_packed_items_volume() > best_bin.get_packed_items_volume()
                 }
+                None => true,
+            };
+            if is_better {
+                best = Some((bin, unpacked_items));
             }
         }
 
-        // If all items are packed
-        if bin.packed_items.len() == sorted_item_list.len() {
-            return Some(bin.clone());
+        match best {
+            Some((bin, unpacked_items)) => {
+                packed_bins.push(bin);
+                remaining_items = unpacked_items.into_iter().map(|(item, _)| item).collect();
+            }
+            // no bin can take any of the remaining items
+            None => {
+                return MultiBinPackingResult {
+                    bins: packed_bins,
+                    unpacked_items: to_unpacked_item_list(&rejected),
+                }
+            }
         }
     }
-    None
+    MultiBinPackingResult {
+        bins: packed_bins,
+        unpacked_items: Vec::new(),
+    }
 }
 
 // pub fn get_smallest_fitting_bin_for_order_vector(
@@ -262,3 +630,174 @@ pub fn get_smallest_fitting_bin_for_item_vector(
 //     }
 //     None
 // }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bin_packing::bin::RawBin;
+    use crate::bin_packing::item::RawItem;
+    use crate::bin_packing::order::RawOrder;
+
+    // Bin with the dimensions [width, height, depth]
+    fn get_bin(name: &str, dimension: [f32; 3], max_weight: f32) -> Bin {
+        RawBin {
+            name: name.to_string(),
+            width: dimension[0],
+            height: dimension[1],
+            depth: dimension[2],
+            max_weight,
+            cog_envelope: None,
+            axles: None,
+        }
+        .convert_to_bin()
+    }
+
+    // Items with the dimensions [width, height, depth]
+    fn get_items(name: &str, quantity: i32, dimension: [f32; 3], weight: f32) -> Vec<Item> {
+        RawOrder {
+            name: name.to_string(),
+            items: vec![RawItem {
+                name: "box".to_string(),
+                quantity,
+                width: dimension[0],
+                height: dimension[1],
+                depth: dimension[2],
+                weight,
+                max_top_load: None,
+            }],
+            orientable: None,
+            stackable: None,
+        }
+        .create_order_from_raw_order()
+        .items
+    }
+
+    #[test]
+    fn items_are_spread_over_as_many_bins_as_needed() {
+        // a container takes 8 cubes, 20 cubes need 3 containers
+        let items = get_items("cubes", 20, [50.0, 50.0, 50.0], 10.0);
+        let bins = [get_bin("container", [100.0, 100.0, 100.0], 1000.0)];
+        let result = pack_item_vector_into_multiple_bins(
+            &items,
+            &bins,
+            &PackingOptions::default(),
+            &SolverMonitor::none(),
+        );
+        assert!(result.unpacked_items.is_empty());
+        assert_eq!(result.bins.len(), 3);
+        let mut names: Vec<&str> = result
+            .bins
+            .iter()
+            .flat_map(|bin| &bin.packed_items)
+            .map(|item| item.name.as_str())
+            .collect();
+        names.sort();
+        names.dedup();
+        assert_eq!(names.len(), 20);
+    }
+
+    #[test]
+    fn last_bin_is_the_smallest_that_takes_the_rest() {
+        // the large bin takes 8 cubes, the 2 left over fit the small bin
+        let items = get_items("cubes", 10, [50.0, 50.0, 50.0], 10.0);
+        let bins = [
+            get_bin("small", [100.0, 50.0, 50.0], 100.0),
+            get_bin("large", [100.0, 100.0, 100.0], 1000.0),
+        ];
+        let result = pack_item_vector_into_multiple_bins(
+            &items,
+            &bins,
+            &PackingOptions::default(),
+            &SolverMonitor::none(),
+        );
+        let bin_names: Vec<&str> = result.bins.iter().map(|bin| bin.name.as_str()).collect();
+        assert_eq!(bin_names, ["large", "small"]);
+        assert!(result.unpacked_items.is_empty());
+    }
+
+    #[test]
+    fn items_no_bin_takes_are_returned_unpacked() {
+        let items = [
+            get_items("cubes", 2, [50.0, 50.0, 50.0], 10.0),
+            get_items("beam", 1, [50.0, 50.0, 500.0], 10.0),
+        ]
+        .concat();
+        let bins = [get_bin("container", [100.0, 100.0, 100.0], 1000.0)];
+        let result = pack_item_vector_into_multiple_bins(
+            &items,
+            &bins,
+            &PackingOptions::default(),
+            &SolverMonitor::none(),
+        );
+        assert_eq!(result.bins.len(), 1);
+        assert_eq!(result.bins[0].packed_items.len(), 2);
+        assert_eq!(result.unpacked_items.len(), 1);
+        assert_eq!(result.unpacked_items[0].name, "beam@box@0");
+    }
+
+    fn get_reasons(bin: &mut Bin, items: &[Item]) -> Vec<(String, UnpackedReason)> {
+        pack_items_into_bin(
+            bin,
+            items,
+            &PackingOptions::default(),
+            0,
+            &SolverMonitor::none(),
+        )
+        .into_iter()
+        .map(|(item, reason)| (item.name, reason))
+        .collect()
+    }
+
+    #[test]
+    fn fullest_bin_is_returned_if_no_bin_takes_every_item() {
+        let items = get_items("cubes", 10, [50.0, 50.0, 50.0], 10.0);
+        let mut bins = [
+            get_bin("small", [100.0, 50.0, 50.0], 1000.0),
+            get_bin("large", [100.0, 100.0, 100.0], 1000.0),
+        ];
+        let result = get_smallest_fitting_bin_for_item_vector(
+            &items,
+            &mut bins,
+            100.0,
+            10.0 * 50.0 * 50.0 * 50.0,
+            &PackingOptions::default(),
+            &SolverMonitor::none(),
+        );
+        let bin = result.bin.unwrap();
+        assert_eq!(bin.name, "large");
+        assert_eq!(bin.packed_items.len(), 8);
+        assert_eq!(result.unpacked_items.len(), 2);
+        assert!(result
+            .unpacked_items
+            .iter()
+            .all(|item| item.reason == UnpackedReason::OverVolume));
+    }
+
+    #[test]
+    fn unpacked_items_carry_their_reason() {
+        let mut bin = get_bin("container", [100.0, 50.0, 100.0], 100.0);
+        let mut standing = get_items("standing", 1, [20.0, 80.0, 20.0], 1.0);
+        standing[0].orientable = Some(false);
+        let items = [
+            get_items("heavy", 1, [10.0, 10.0, 10.0], 500.0),
+            standing,
+            get_items("long", 1, [10.0, 10.0, 200.0], 1.0),
+            get_items("filler", 1, [100.0, 50.0, 100.0], 1.0),
+            get_items("late", 1, [10.0, 10.0, 10.0], 1.0),
+        ]
+        .concat();
+        let reasons = get_reasons(&mut bin, &items);
+        assert_eq!(
+            reasons,
+            [
+                ("heavy@box@0".to_string(), UnpackedReason::OverWeight),
+                (
+                    "standing@box@0".to_string(),
+                    UnpackedReason::OrientationForbidden
+                ),
+                ("long@box@0".to_string(), UnpackedReason::NoFreePosition),
+                ("late@box@0".to_string(), UnpackedReason::OverVolume),
+            ]
+        );
+    }
+}
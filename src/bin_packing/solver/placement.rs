@@ -0,0 +1,239 @@
+use crate::bin_packing::bin::Bin;
+use crate::bin_packing::options::PlacementPolicy;
+
+const TOLERANCE: f32 = 1e-4;
+
+// Length of the overlap of [a_start, a_start + a_length) and [b_start, b_start + b_length)
+fn get_overlap(a_start: f32, a_length: f32, b_start: f32, b_length: f32) -> f32 {
+    ((a_start + a_length).min(b_start + b_length) - a_start.max(b_start)).max(0.0)
+}
+
+// Area of the item faces touching packed items or the bin walls
+fn get_contact_area(bin: &Bin, pivot: &[f32; 3], item_dimension: &[f32; 3]) -> f32 {
+    let bin_dimension = [bin.width, bin.height, bin.depth];
+    let mut area = 0.0;
+
+    for axis in 0..3 {
+        let (u, v) = ((axis + 1) % 3, (axis + 2) % 3);
+        let face_area = item_dimension[u] * item_dimension[v];
+
+        // bin walls
+        if pivot[axis] <= TOLERANCE {
+            area += face_area;
+        }
+        if (pivot[axis] + item_dimension[axis] - bin_dimension[axis]).abs() <= TOLERANCE {
+            area += face_area;
+        }
+
+        // faces of packed items
+        for item_in_bin in &bin.packed_items {
+            let dimension = item_in_bin.get_rotated_dimension(&item_in_bin.rotation);
+            let position = &item_in_bin.position;
+            let touches = (position[axis] + dimension[axis] - pivot[axis]).abs() <= TOLERANCE
+                || (pivot[axis] + item_dimension[axis] - position[axis]).abs() <= TOLERANCE;
+            if touches {
+                area += get_overlap(pivot[u], item_dimension[u], position[u], dimension[u])
+                    * get_overlap(pivot[v], item_dimension[v], position[v], dimension[v]);
+            }
+        }
+    }
+    area
+}
+
+/*
+    Distance from the pivot along the positive direction of the axis
+    to the nearest packed item in the way, or to the bin wall
+*/
+fn get_free_distance(bin: &Bin, pivot: &[f32; 3], axis: usize) -> f32 {
+    let bin_dimension = [bin.width, bin.height, bin.depth];
+    let others: Vec<usize> = (0..3).filter(|&other| other != axis).collect();
+
+    bin.packed_items
+        .iter()
+        .filter_map(|item_in_bin| {
+            let dimension = item_in_bin.get_rotated_dimension(&item_in_bin.rotation);
+            let position = &item_in_bin.position;
+            let in_the_way = others.iter().all(|&other| {
+                pivot[other] >= position[other] - TOLERANCE
+                    && pivot[other] < position[other] + dimension[other] - TOLERANCE
+            });
+            (in_the_way && position[axis] >= pivot[axis] - TOLERANCE).then_some(position[axis])
+        })
+        .fold(bin_dimension[axis], f32::min)
+        - pivot[axis]
+}
+
+/*
+    Score of placing an item with the given rotated dimension at the pivot, lower is better.
+    Scores are compared element by element, later elements break ties.
+*/
+pub fn get_placement_score(
+    bin: &Bin,
+    policy: PlacementPolicy,
+    pivot: &[f32; 3],
+    item_dimension: &[f32; 3],
+) -> [f32; 3] {
+    match policy {
+        PlacementPolicy::FirstFit | PlacementPolicy::BottomLeftBack => {
+            [pivot[1], pivot[2], pivot[0]]
+        }
+        PlacementPolicy::LowestHeight => [pivot[1] + item_dimension[1], pivot[2], pivot[0]],
+        PlacementPolicy::BestContactArea => [
+            -get_contact_area(bin, pivot, item_dimension),
+            pivot[1],
+            pivot[2],
+        ],
+        PlacementPolicy::MinResidualSpace => {
+            let residual_space: f32 = (0..3)
+                .map(|axis| get_free_distance(bin, pivot, axis) - item_dimension[axis])
+                .sum();
+            [residual_space, pivot[1], pivot[2]]
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bin_packing::bin::RawBin;
+    use crate::bin_packing::item::{Item, RawItem};
+    use crate::bin_packing::options::PackingOptions;
+    use crate::bin_packing::order::RawOrder;
+    use crate::bin_packing::progress::SolverMonitor;
+    use crate::bin_packing::solver::pack_items_into_bin;
+
+    const POLICIES: [PlacementPolicy; 5] = [
+        PlacementPolicy::FirstFit,
+        PlacementPolicy::BottomLeftBack,
+        PlacementPolicy::LowestHeight,
+        PlacementPolicy::BestContactArea,
+        PlacementPolicy::MinResidualSpace,
+    ];
+
+    fn get_bin() -> Bin {
+        RawBin {
+            name: "container".to_string(),
+            width: 100.0,
+            height: 100.0,
+            depth: 100.0,
+            max_weight: 1000.0,
+            cog_envelope: None,
+            axles: None,
+        }
+        .convert_to_bin()
+    }
+
+    // Items with the dimensions [width, height, depth]
+    fn get_items(quantity: i32, dimension: [f32; 3]) -> Vec<Item> {
+        RawOrder {
+            name: "order".to_string(),
+            items: vec![RawItem {
+                name: "box".to_string(),
+                quantity,
+                width: dimension[0],
+                height: dimension[1],
+                depth: dimension[2],
+                weight: 1.0,
+                max_top_load: None,
+            }],
+            orientable: None,
+            stackable: None,
+        }
+        .create_order_from_raw_order()
+        .items
+    }
+
+    // Pivot with the lowest score for the item dimension
+    fn get_best_pivot(
+        bin: &Bin,
+        policy: PlacementPolicy,
+        pivots: &[[f32; 3]],
+        item_dimension: &[f32; 3],
+    ) -> [f32; 3] {
+        *pivots
+            .iter()
+            .min_by(|a, b| {
+                let score_a = get_placement_score(bin, policy, a, item_dimension);
+                let score_b = get_placement_score(bin, policy, b, item_dimension);
+                score_a.partial_cmp(&score_b).unwrap()
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn policies_score_the_pivots_their_own_way() {
+        let mut bin = get_bin();
+        let packed_item = get_items(1, [50.0, 20.0, 100.0]).remove(0);
+        bin.packed_items.push(packed_item);
+        let pivots = [[0.0, 20.0, 0.0], [50.0, 0.0, 0.0], [70.0, 0.0, 0.0]];
+
+        let item_dimension = [30.0, 50.0, 100.0];
+        // lowest, then front most, then left most
+        assert_eq!(
+            get_best_pivot(
+                &bin,
+                PlacementPolicy::BottomLeftBack,
+                &pivots,
+                &item_dimension
+            ),
+            [50.0, 0.0, 0.0]
+        );
+        // at the right wall the item touches the floor, the bin walls front, back and right
+        assert_eq!(
+            get_best_pivot(
+                &bin,
+                PlacementPolicy::BestContactArea,
+                &pivots,
+                &item_dimension
+            ),
+            [70.0, 0.0, 0.0]
+        );
+        // a flat item ends lowest on top of the packed item
+        let flat_dimension = [50.0, 10.0, 100.0];
+        let floor_pivots = [[0.0, 20.0, 0.0], [50.0, 0.0, 0.0]];
+        assert_eq!(
+            get_best_pivot(
+                &bin,
+                PlacementPolicy::LowestHeight,
+                &floor_pivots,
+                &item_dimension
+            ),
+            [50.0, 0.0, 0.0]
+        );
+        assert_eq!(
+            get_placement_score(
+                &bin,
+                PlacementPolicy::LowestHeight,
+                &floor_pivots[0],
+                &flat_dimension
+            )[0],
+            30.0
+        );
+        // the gap next to the packed item is exactly as wide as the item
+        let gap_dimension = [50.0, 80.0, 100.0];
+        assert_eq!(
+            get_placement_score(
+                &bin,
+                PlacementPolicy::MinResidualSpace,
+                &[50.0, 0.0, 0.0],
+                &gap_dimension
+            )[0],
+            20.0
+        );
+    }
+
+    #[test]
+    fn every_policy_fills_the_bin() {
+        for policy in POLICIES {
+            let options = PackingOptions {
+                placement_policy: policy,
+                ..Default::default()
+            };
+            let mut bin = get_bin();
+            let items = get_items(8, [50.0, 50.0, 50.0]);
+            let unpacked =
+                pack_items_into_bin(&mut bin, &items, &options, 0, &SolverMonitor::none());
+            assert!(unpacked.is_empty(), "{policy:?}");
+        }
+    }
+}
@@ -0,0 +1,104 @@
+/*
+    Loading rules for dimensionless orders.
+    Orders of a group are loaded all-or-none, orders of conflicting categories never share a bin.
+*/
+use crate::bin_packing::order::DimensionLessOrder;
+use crate::bin_packing::solver::knapsack::{intersects, union_into, ClassSet};
+use std::collections::BTreeMap;
+
+// Categories named in conflict pairs, each gets one bit of a ClassSet
+pub struct ConflictRules {
+    class_index: BTreeMap<String, usize>,
+    // classes every class conflicts with
+    conflicts: Vec<ClassSet>,
+}
+
+impl ConflictRules {
+    pub fn new(conflict_pairs: &[(String, String)]) -> ConflictRules {
+        let mut class_index = BTreeMap::new();
+        for (a, b) in conflict_pairs {
+            for category in [a, b] {
+                let next_index = class_index.len();
+                class_index.entry(category.clone()).or_insert(next_index);
+            }
+        }
+
+        let words = class_index.len().div_ceil(64);
+        let mut conflicts = vec![vec![0; words]; class_index.len()];
+        for (a, b) in conflict_pairs {
+            let (a, b) = (class_index[a], class_index[b]);
+            conflicts[a][b / 64] |= 1 << (b % 64);
+            conflicts[b][a / 64] |= 1 << (a % 64);
+        }
+
+        ConflictRules {
+            class_index,
+            conflicts,
+        }
+    }
+
+    pub fn get_empty_classes(&self) -> ClassSet {
+        vec![0; self.class_index.len().div_ceil(64)]
+    }
+
+    // Classes of the order, empty if its category is in no conflict pair
+    pub fn get_classes(&self, order: &DimensionLessOrder) -> ClassSet {
+        let mut classes = self.get_empty_classes();
+        if let Some(&index) = order
+            .category
+            .as_ref()
+            .and_then(|category| self.class_index.get(category))
+        {
+            classes[index / 64] |= 1 << (index % 64);
+        }
+        classes
+    }
+
+    // Classes that may not share a bin with any of the given classes
+    pub fn get_conflicts(&self, classes: &[u64]) -> ClassSet {
+        let mut conflicts = self.get_empty_classes();
+        for (index, class_conflicts) in self.conflicts.iter().enumerate() {
+            if classes[index / 64] & (1 << (index % 64)) != 0 {
+                union_into(&mut conflicts, class_conflicts);
+            }
+        }
+        conflicts
+    }
+
+    // Classes of all orders, None if two of them conflict with each other
+    pub fn get_combined_classes(&self, orders: &[&DimensionLessOrder]) -> Option<ClassSet> {
+        let mut combined = self.get_empty_classes();
+        for order in orders {
+            let classes = self.get_classes(order);
+            if intersects(&self.get_conflicts(&classes), &combined)
+                || intersects(&self.get_conflicts(&combined), &classes)
+            {
+                return None;
+            }
+            union_into(&mut combined, &classes);
+        }
+        Some(combined)
+    }
+}
+
+/*
+    Splits the orders into groups that are loaded all-or-none.
+    Orders without a group form a group of their own, groups keep the order of their first order.
+*/
+pub fn get_order_groups(orders: &[DimensionLessOrder]) -> Vec<Vec<&DimensionLessOrder>> {
+    let mut groups: Vec<Vec<&DimensionLessOrder>> = Vec::new();
+    let mut group_index: BTreeMap<&str, usize> = BTreeMap::new();
+    for order in orders {
+        match order.group.as_deref() {
+            Some(group) => match group_index.get(group) {
+                Some(&index) => groups[index].push(order),
+                None => {
+                    group_index.insert(group, groups.len());
+                    groups.push(vec![order]);
+                }
+            },
+            None => groups.push(vec![order]),
+        }
+    }
+    groups
+}
@@ -0,0 +1,491 @@
+/*
+    Runs the constructive solver several times and keeps the best plan.
+    Optionally improves the best plan with simulated annealing.
+*/
+use crate::bin_packing::bin::Bin;
+use crate::bin_packing::item::Item;
+use crate::bin_packing::options::{
+    ImprovementOptions, ItemOrdering, PackingOptions, PlacementPolicy, PlanObjective,
+};
+use crate::bin_packing::progress::{ProgressEvent, SolverMonitor};
+use crate::bin_packing::result::{MultiBinPackingResult, PackingResult};
+use crate::bin_packing::solver::clock::Stopwatch;
+use crate::bin_packing::solver::random::Random;
+use crate::bin_packing::solver::{
+    get_allowed_rotations, get_smallest_fitting_bin_for_item_vector,
+    pack_item_vector_into_multiple_bins,
+};
+use crate::bin_packing::sort_item_list;
+
+// Orderings to try, the multi start list or the single configured ordering
+fn get_item_orderings(options: &PackingOptions) -> Vec<ItemOrdering> {
+    if options.multi_start.is_empty() {
+        vec![options.item_ordering]
+    } else {
+        options.multi_start.clone()
+    }
+}
+
+// Number of constructive runs and improvement iterations, the steps the search reports progress by
+fn get_search_steps(options: &PackingOptions) -> usize {
+    let improvement_steps = options
+        .improvement
+        .as_ref()
+        .map_or(0, |improvement| improvement.max_iterations as usize);
+    get_item_orderings(options).len() + improvement_steps
+}
+
+// A single run reports its bins and items, the runs of a search only report through the search
+fn get_run_monitor<'a>(monitor: &SolverMonitor<'a>, total_steps: usize) -> SolverMonitor<'a> {
+    match total_steps {
+        1 => *monitor,
+        _ => monitor.silent(),
+    }
+}
+
+fn report_percentage(monitor: &SolverMonitor, done_steps: usize, total_steps: usize) {
+    monitor.report(ProgressEvent::Percentage {
+        percent: 100.0 * done_steps as f32 / total_steps.max(1) as f32,
+    });
+}
+
+// Complete plans win, between complete plans the smaller bin, then the objective
+pub fn is_better_packing(
+    result: &PackingResult,
+    other: &PackingResult,
+    objective: PlanObjective,
+) -> bool {
+    let key = |result: &PackingResult| {
+        let bin_volume = match (result.is_complete(), &result.bin) {
+            (true, Some(bin)) => bin.get_volume(),
+            _ => 0.0,
+        };
+        (
+            result.is_complete(),
+            -bin_volume,
+            result.get_objective_value(objective),
+        )
+    };
+    key(result) > key(other)
+}
+
+// Fewer unpacked items win, then fewer bins, then the objective
+pub fn is_better_multi_bin_packing(
+    result: &MultiBinPackingResult,
+    other: &MultiBinPackingResult,
+    objective: PlanObjective,
+) -> bool {
+    let key = |result: &MultiBinPackingResult| {
+        (
+            -(result.unpacked_items.len() as f32),
+            -(result.bins.len() as f32),
+            result.get_objective_value(objective),
+        )
+    };
+    key(result) > key(other)
+}
+
+/*
+    Smallest fitting bin for the items, trying every configured item ordering.
+    bin_list must be sorted the way bins should be tried.
+*/
+pub fn get_best_fitting_bin_for_item_vector(
+    item_list: &[Item],
+    bin_list: &[Bin],
+    total_order_weight: f32,
+    total_order_volume: f32,
+    options: &PackingOptions,
+    monitor: &SolverMonitor,
+) -> PackingResult {
+    let total_steps = get_search_steps(options);
+    let run_monitor = get_run_monitor(monitor, total_steps);
+    let mut best: Option<(PackingResult, Vec<Item>)> = None;
+    for (step, ordering) in get_item_orderings(options).into_iter().enumerate() {
+        if monitor.is_cancelled() && best.is_some() {
+            break;
+        }
+        let mut sorted_item_list = item_list.to_vec();
+        sort_item_list(&mut sorted_item_list, ordering);
+
+        let result = get_smallest_fitting_bin_for_item_vector(
+            &sorted_item_list,
+            &mut bin_list.to_vec(),
+            total_order_weight,
+            total_order_volume,
+            options,
+            &run_monitor,
+        );
+        if best
+            .as_ref()
+            .is_none_or(|(best, _)| is_better_packing(&result, best, options.objective))
+        {
+            monitor.report(ProgressEvent::BestSoFar {
+                utilisation: result.get_objective_value(PlanObjective::Utilisation),
+            });
+            best = Some((result, sorted_item_list));
+        }
+        report_percentage(monitor, step + 1, total_steps);
+    }
+    // at least one ordering is always tried
+    let (result, sequence) = best.unwrap();
+
+    let result = match &options.improvement {
+        Some(improvement) => improve_fitting_bin_for_item_vector(
+            sequence,
+            result,
+            bin_list,
+            total_order_weight,
+            total_order_volume,
+            options,
+            improvement,
+            monitor,
+        ),
+        None => result,
+    };
+    report_percentage(monitor, total_steps, total_steps);
+    result
+}
+
+/*
+    Relative loss of the objective when moving from the current plan to the candidate.
+    Losing or gaining a complete plan, or a smaller bin for a complete plan, counts as a full loss or gain.
+*/
+fn get_relative_loss(
+    current: &PackingResult,
+    candidate: &PackingResult,
+    objective: PlanObjective,
+) -> f32 {
+    let bin_volume =
+        |result: &PackingResult| result.bin.as_ref().map_or(0.0, |bin| bin.get_volume());
+    match (current.is_complete(), candidate.is_complete()) {
+        (true, false) => 1.0,
+        (false, true) => -1.0,
+        (true, true) if bin_volume(candidate) != bin_volume(current) => {
+            match bin_volume(candidate) > bin_volume(current) {
+                true => 1.0,
+                false => -1.0,
+            }
+        }
+        _ => {
+            let current_value = current.get_objective_value(objective);
+            let candidate_value = candidate.get_objective_value(objective);
+            (current_value - candidate_value) / current_value.abs().max(f32::EPSILON)
+        }
+    }
+}
+
+/*
+    Swaps two items of the sequence or moves a block of items to another place.
+    First fit takes the first rotation that fits, so it may also change the rotation tried first for one item,
+    the other policies compare every rotation anyway.
+*/
+fn perturb_sequence(sequence: &[Item], policy: PlacementPolicy, random: &mut Random) -> Vec<Item> {
+    let mut candidate = sequence.to_vec();
+    let index = random.next_index(candidate.len());
+    let moves = match policy {
+        PlacementPolicy::FirstFit => 3,
+        _ => 2,
+    };
+    match random.next_index(moves) {
+        0 if candidate.len() > 1 => {
+            let other = random.next_index(candidate.len());
+            candidate.swap(index, other);
+        }
+        1 if candidate.len() > 1 => {
+            // blocks of up to a quarter of the sequence keep items packed together in the same order
+            let max_length = (candidate.len() / 4).clamp(1, candidate.len() - index);
+            let length = 1 + random.next_index(max_length);
+            let block: Vec<Item> = candidate.drain(index..index + length).collect();
+            let target = random.next_index(candidate.len() + 1);
+            candidate.splice(target..target, block);
+        }
+        _ if policy == PlacementPolicy::FirstFit => {
+            let rotations = get_allowed_rotations(candidate[index].orientable.unwrap_or(true));
+            candidate[index].rotation = rotations[random.next_index(rotations.len())].to_string();
+        }
+        _ => {}
+    }
+    candidate
+}
+
+/*
+    Simulated annealing on top of the constructive solver.
+    Every move packs the whole perturbed sequence again, worse plans are accepted
+    with a probability that shrinks as the temperature cools down.
+*/
+#[allow(clippy::too_many_arguments)]
+fn improve_fitting_bin_for_item_vector(
+    sequence: Vec<Item>,
+    result: PackingResult,
+    bin_list: &[Bin],
+    total_order_weight: f32,
+    total_order_volume: f32,
+    options: &PackingOptions,
+    improvement: &ImprovementOptions,
+    monitor: &SolverMonitor,
+) -> PackingResult {
+    let mut random = Random::new(improvement.seed);
+    let stopwatch = Stopwatch::start();
+    let mut temperature = improvement.initial_temperature;
+
+    let mut current_sequence = sequence;
+    let mut current_result = result.clone();
+    let mut best_result = result;
+
+    let total_steps = get_search_steps(options);
+    let done_steps = get_item_orderings(options).len();
+    let run_monitor = monitor.silent();
+    for iteration in 0..improvement.max_iterations as usize {
+        if current_sequence.is_empty() || monitor.is_cancelled() {
+            break;
+        }
+        if let Some(time_limit_ms) = improvement.time_limit_ms {
+            if stopwatch.get_elapsed_ms() >= time_limit_ms {
+                break;
+            }
+        }
+
+        let candidate_sequence =
+            perturb_sequence(&current_sequence, options.placement_policy, &mut random);
+        let candidate_result = get_smallest_fitting_bin_for_item_vector(
+            &candidate_sequence,
+            &mut bin_list.to_vec(),
+            total_order_weight,
+            total_order_volume,
+            options,
+            &run_monitor,
+        );
+
+        let loss = get_relative_loss(&current_result, &candidate_result, options.objective);
+        let accepted =
+            loss <= 0.0 || (temperature > 0.0 && random.next_f32() < (-loss / temperature).exp());
+        if accepted {
+            if is_better_packing(&candidate_result, &best_result, options.objective) {
+                monitor.report(ProgressEvent::BestSoFar {
+                    utilisation: candidate_result.get_objective_value(PlanObjective::Utilisation),
+                });
+                best_result = candidate_result.clone();
+            }
+            current_sequence = candidate_sequence;
+            current_result = candidate_result;
+        }
+        temperature *= improvement.cooling_rate;
+        report_percentage(monitor, done_steps + iteration + 1, total_steps);
+    }
+    best_result
+}
+
+// Multi bin packing of the items, trying every configured item ordering
+pub fn get_best_multi_bin_packing(
+    item_list: &[Item],
+    bin_list: &[Bin],
+    options: &PackingOptions,
+    monitor: &SolverMonitor,
+) -> MultiBinPackingResult {
+    let orderings = get_item_orderings(options);
+    let total_steps = orderings.len();
+    let run_monitor = get_run_monitor(monitor, total_steps);
+    let mut best: Option<MultiBinPackingResult> = None;
+    for (step, ordering) in orderings.into_iter().enumerate() {
+        if monitor.is_cancelled() && best.is_some() {
+            break;
+        }
+        let mut sorted_item_list = item_list.to_vec();
+        sort_item_list(&mut sorted_item_list, ordering);
+
+        let result =
+            pack_item_vector_into_multiple_bins(&sorted_item_list, bin_list, options, &run_monitor);
+        if best
+            .as_ref()
+            .is_none_or(|best| is_better_multi_bin_packing(&result, best, options.objective))
+        {
+            monitor.report(ProgressEvent::BestSoFar {
+                utilisation: result.get_objective_value(PlanObjective::Utilisation),
+            });
+            best = Some(result);
+        }
+        report_percentage(monitor, step + 1, total_steps);
+    }
+    // at least one ordering is always tried
+    best.unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bin_packing::api;
+    use crate::bin_packing::bin::RawBin;
+    use crate::bin_packing::error::PackingError;
+    use crate::bin_packing::item::RawItem;
+    use crate::bin_packing::order::RawOrder;
+    use std::cell::{Cell, RefCell};
+
+    fn get_raw_bins() -> Vec<RawBin> {
+        vec![RawBin {
+            name: "truck".to_string(),
+            depth: 300.0,
+            width: 200.0,
+            height: 200.0,
+            max_weight: 5000.0,
+            cog_envelope: None,
+            axles: None,
+        }]
+    }
+
+    fn get_raw_orders() -> Vec<RawOrder> {
+        vec![RawOrder {
+            name: "order".to_string(),
+            items: vec![RawItem {
+                name: "box".to_string(),
+                quantity: 12,
+                depth: 50.0,
+                width: 40.0,
+                height: 30.0,
+                weight: 20.0,
+                max_top_load: None,
+            }],
+            orientable: None,
+            stackable: None,
+        }]
+    }
+
+    fn get_events(options: &PackingOptions) -> Vec<ProgressEvent> {
+        let events = RefCell::new(Vec::new());
+        let progress = |event: &ProgressEvent| events.borrow_mut().push(event.clone());
+        api::get_smallest_fitting_bin_for_order_list(
+            &get_raw_orders(),
+            &get_raw_bins(),
+            options,
+            &SolverMonitor::new(&progress, &()),
+        )
+        .unwrap();
+        events.into_inner()
+    }
+
+    #[test]
+    fn complete_plan_in_the_smaller_bin_wins() {
+        let get_result = |width: f32| PackingResult {
+            bin: Some(
+                RawBin {
+                    width,
+                    ..get_raw_bins().remove(0)
+                }
+                .convert_to_bin(),
+            ),
+            unpacked_items: Vec::new(),
+            violations: Vec::new(),
+        };
+        let (small, large) = (get_result(100.0), get_result(200.0));
+        for objective in [PlanObjective::Utilisation, PlanObjective::PackedWeight] {
+            assert!(is_better_packing(&small, &large, objective));
+            assert!(!is_better_packing(&large, &small, objective));
+        }
+        assert_eq!(
+            get_relative_loss(&small, &large, PlanObjective::PackedWeight),
+            1.0
+        );
+    }
+
+    #[test]
+    fn perturbed_sequence_keeps_every_item() {
+        let items = get_raw_orders()[0].create_order_from_raw_order().items;
+        let mut random = Random::new(3);
+        let mut sequence = items.clone();
+        for _ in 0..100 {
+            sequence = perturb_sequence(&sequence, PlacementPolicy::BestContactArea, &mut random);
+            let mut names: Vec<&str> = sequence.iter().map(|item| item.name.as_str()).collect();
+            names.sort();
+            assert_eq!(names.len(), items.len());
+            names.dedup();
+            assert_eq!(names.len(), items.len());
+            // only first fit depends on the rotation tried first
+            assert!(sequence.iter().all(|item| item.rotation == "RT_WHD"));
+        }
+    }
+
+    #[test]
+    fn same_seed_gives_the_same_plan() {
+        let get_positions = |seed: u64| {
+            let options = PackingOptions {
+                improvement: Some(ImprovementOptions {
+                    max_iterations: 50,
+                    seed,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            };
+            let result = api::get_smallest_fitting_bin_for_order_list(
+                &get_raw_orders(),
+                &get_raw_bins(),
+                &options,
+                &SolverMonitor::none(),
+            )
+            .unwrap();
+            let bin = result.bin.unwrap();
+            bin.packed_items
+                .iter()
+                .map(|item| (item.name.clone(), item.rotation.clone(), item.position))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(get_positions(7), get_positions(7));
+    }
+
+    #[test]
+    fn single_run_reports_every_placed_item() {
+        let events = get_events(&PackingOptions::default());
+        let placed_items = events
+            .iter()
+            .filter(|event| matches!(event, ProgressEvent::ItemPlaced { .. }))
+            .count();
+        assert_eq!(placed_items, 12);
+        assert!(matches!(events[0], ProgressEvent::BinStarted { .. }));
+    }
+
+    #[test]
+    fn search_reports_only_its_own_progress() {
+        let options = PackingOptions {
+            multi_start: vec![ItemOrdering::Weight, ItemOrdering::Volume],
+            improvement: Some(ImprovementOptions {
+                max_iterations: 10,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let events = get_events(&options);
+        let mut last_percent = 0.0;
+        for event in &events {
+            match event {
+                ProgressEvent::Percentage { percent } => {
+                    assert!(*percent >= last_percent);
+                    last_percent = *percent;
+                }
+                ProgressEvent::BestSoFar { .. } => {}
+                event => panic!("unexpected event {event:?}"),
+            }
+        }
+        assert_eq!(last_percent, 100.0);
+    }
+
+    #[test]
+    fn cancelled_search_stops_with_an_error() {
+        let polls = Cell::new(0);
+        // cancelled after the first few items of the first run
+        let cancellation = || {
+            polls.set(polls.get() + 1);
+            polls.get() > 5
+        };
+        let options = PackingOptions {
+            improvement: Some(ImprovementOptions::default()),
+            ..Default::default()
+        };
+        let result = api::get_smallest_fitting_bin_for_order_list(
+            &get_raw_orders(),
+            &get_raw_bins(),
+            &options,
+            &SolverMonitor::new(&(), &cancellation),
+        );
+        assert_eq!(result.unwrap_err(), PackingError::Cancelled);
+        // the search does not go on after the cancellation
+        assert!(polls.get() < 50);
+    }
+}
@@ -0,0 +1,288 @@
+use crate::bin_packing::bin::Bin;
+
+// tolerance used when comparing the base of an item with the top face of another item
+const LEVEL_TOLERANCE: f32 = 1e-4;
+
+// Overlap area of two boxes projected onto the bin floor (width x depth)
+fn get_footprint_overlap_area(
+    existing_item_dimension: &[f32; 3],
+    existing_item_position: &[f32; 3],
+    new_item_dimension: &[f32; 3],
+    new_item_position: &[f32; 3],
+) -> f32 {
+    let overlap_x = (existing_item_position[0] + existing_item_dimension[0])
+        .min(new_item_position[0] + new_item_dimension[0])
+        - existing_item_position[0].max(new_item_position[0]);
+    let overlap_z = (existing_item_position[2] + existing_item_dimension[2])
+        .min(new_item_position[2] + new_item_dimension[2])
+        - existing_item_position[2].max(new_item_position[2]);
+
+    if overlap_x <= 0.0 || overlap_z <= 0.0 {
+        return 0.0;
+    }
+    overlap_x * overlap_z
+}
+
+/*
+    Returns the packed items whose top face carries the base of the new item,
+    as (index in bin.packed_items, contact area)
+*/
+pub fn get_supporting_items(
+    bin: &Bin,
+    new_item_dimension: &[f32; 3],
+    new_item_position: &[f32; 3],
+) -> Vec<(usize, f32)> {
+    let mut supporting_items = Vec::new();
+    for (index, item_in_bin) in bin.packed_items.iter().enumerate() {
+        let dimension = item_in_bin.get_rotated_dimension(&item_in_bin.rotation);
+        let top = item_in_bin.position[1] + dimension[1];
+        if (top - new_item_position[1]).abs() > LEVEL_TOLERANCE {
+            continue;
+        }
+        let area = get_footprint_overlap_area(
+            &dimension,
+            &item_in_bin.position,
+            new_item_dimension,
+            new_item_position,
+        );
+        if area > 0.0 {
+            supporting_items.push((index, area));
+        }
+    }
+    supporting_items
+}
+
+// Share of the new item's base area that rests on the bin floor or on top faces of packed items
+pub fn get_support_ratio(
+    bin: &Bin,
+    new_item_dimension: &[f32; 3],
+    new_item_position: &[f32; 3],
+) -> f32 {
+    // item stands on the bin floor
+    if new_item_position[1] <= LEVEL_TOLERANCE {
+        return 1.0;
+    }
+
+    let base_area = new_item_dimension[0] * new_item_dimension[2];
+    if base_area <= 0.0 {
+        return 1.0;
+    }
+
+    let supported_area: f32 = get_supporting_items(bin, new_item_dimension, new_item_position)
+        .iter()
+        .map(|(_, area)| area)
+        .sum();
+    // packed items never overlap, but keep the ratio bounded against rounding errors
+    (supported_area / base_area).min(1.0)
+}
+
+// Items never float in mid-air, a min support ratio above 0.0 asks for more of the base to be supported
+pub fn is_supported(support_ratio: f32, min_support_ratio: f32) -> bool {
+    support_ratio > 0.0 && support_ratio >= min_support_ratio
+}
+
+// Spreads a load over the items below, in proportion to the contact area
+fn spread_load(
+    bin: &Bin,
+    item_dimension: &[f32; 3],
+    item_position: &[f32; 3],
+    load: f32,
+    added_loads: &mut [f32],
+) {
+    let supporting_items = get_supporting_items(bin, item_dimension, item_position);
+    let total_area: f32 = supporting_items.iter().map(|(_, area)| area).sum();
+    if total_area <= 0.0 {
+        // item rests on the bin floor
+        return;
+    }
+    for (index, area) in supporting_items {
+        added_loads[index] += load * area / total_area;
+    }
+}
+
+/*
+    Returns the extra top load every packed item would carry if the new item was placed,
+    indexed like bin.packed_items. The weight is passed down through the whole stack.
+*/
+pub fn get_added_top_loads(
+    bin: &Bin,
+    new_item_dimension: &[f32; 3],
+    new_item_position: &[f32; 3],
+    new_item_weight: f32,
+) -> Vec<f32> {
+    let mut added_loads = vec![0.0; bin.packed_items.len()];
+    spread_load(
+        bin,
+        new_item_dimension,
+        new_item_position,
+        new_item_weight,
+        &mut added_loads,
+    );
+
+    // Upper items first, so each item passes on everything it received from above
+    let mut indices: Vec<usize> = (0..bin.packed_items.len()).collect();
+    indices.sort_by(|&a, &b| {
+        bin.packed_items[b].position[1].total_cmp(&bin.packed_items[a].position[1])
+    });
+    for index in indices {
+        if added_loads[index] <= 0.0 {
+            continue;
+        }
+        let item_in_bin = &bin.packed_items[index];
+        spread_load(
+            bin,
+            &item_in_bin.get_rotated_dimension(&item_in_bin.rotation),
+            &item_in_bin.position,
+            added_loads[index],
+            &mut added_loads,
+        );
+    }
+    added_loads
+}
+
+// Check if every item below can carry the extra load
+pub fn check_top_loads(bin: &Bin, added_loads: &[f32]) -> bool {
+    bin.packed_items
+        .iter()
+        .zip(added_loads)
+        .all(
+            |(item_in_bin, &added_load)| match item_in_bin.get_top_load_limit() {
+                Some(limit) if added_load > 0.0 => item_in_bin.top_load + added_load <= limit,
+                _ => true,
+            },
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bin_packing::bin::RawBin;
+    use crate::bin_packing::item::{Item, RawItem};
+    use crate::bin_packing::options::PackingOptions;
+    use crate::bin_packing::order::RawOrder;
+    use crate::bin_packing::progress::SolverMonitor;
+    use crate::bin_packing::result::UnpackedReason;
+    use crate::bin_packing::solver::pack_items_into_bin;
+
+    fn get_bin() -> Bin {
+        RawBin {
+            name: "container".to_string(),
+            width: 100.0,
+            height: 100.0,
+            depth: 100.0,
+            max_weight: 1000.0,
+            cog_envelope: None,
+            axles: None,
+        }
+        .convert_to_bin()
+    }
+
+    // Upright item with the dimensions [width, height, depth]
+    fn get_item(name: &str, dimension: [f32; 3], weight: f32, max_top_load: Option<f32>) -> Item {
+        let mut items = RawOrder {
+            name: name.to_string(),
+            items: vec![RawItem {
+                name: "box".to_string(),
+                quantity: 1,
+                width: dimension[0],
+                height: dimension[1],
+                depth: dimension[2],
+                weight,
+                max_top_load,
+            }],
+            orientable: Some(false),
+            stackable: None,
+        }
+        .create_order_from_raw_order()
+        .items;
+        items.remove(0)
+    }
+
+    fn place(bin: &mut Bin, item: Item, position: [f32; 3]) {
+        bin.packed_items.push(Item { position, ..item });
+    }
+
+    #[test]
+    fn support_ratio_is_the_share_of_the_base_on_top_faces() {
+        let mut bin = get_bin();
+        place(
+            &mut bin,
+            get_item("cube", [50.0, 20.0, 50.0], 1.0, None),
+            [0.0; 3],
+        );
+        let plate = [100.0, 10.0, 100.0];
+        assert_eq!(get_support_ratio(&bin, &plate, &[0.0, 0.0, 0.0]), 1.0);
+        assert_eq!(get_support_ratio(&bin, &plate, &[0.0, 20.0, 0.0]), 0.25);
+        assert_eq!(get_support_ratio(&bin, &plate, &[0.0, 30.0, 0.0]), 0.0);
+        // nothing ever floats, even without a minimum ratio
+        assert!(!is_supported(0.0, 0.0));
+        assert!(is_supported(0.25, 0.0));
+        assert!(!is_supported(0.25, 0.75));
+    }
+
+    #[test]
+    fn items_need_the_minimum_support_ratio() {
+        // the plate only fits on top of the cube, resting on a quarter of its base
+        let items = [
+            get_item("cube", [50.0, 50.0, 50.0], 1.0, None),
+            get_item("plate", [100.0, 10.0, 100.0], 1.0, None),
+        ];
+        for (min_support_ratio, expected) in
+            [(0.0, Vec::new()), (0.75, vec![UnpackedReason::Unsupported])]
+        {
+            let options = PackingOptions {
+                min_support_ratio,
+                ..Default::default()
+            };
+            let unpacked =
+                pack_items_into_bin(&mut get_bin(), &items, &options, 0, &SolverMonitor::none());
+            let reasons: Vec<UnpackedReason> =
+                unpacked.into_iter().map(|(_, reason)| reason).collect();
+            assert_eq!(reasons, expected);
+        }
+    }
+
+    #[test]
+    fn top_load_is_passed_down_the_whole_stack() {
+        let mut bin = get_bin();
+        place(
+            &mut bin,
+            get_item("left", [50.0, 10.0, 100.0], 1.0, None),
+            [0.0; 3],
+        );
+        place(
+            &mut bin,
+            get_item("right", [50.0, 10.0, 100.0], 1.0, None),
+            [50.0, 0.0, 0.0],
+        );
+        place(
+            &mut bin,
+            get_item("middle", [100.0, 10.0, 100.0], 1.0, None),
+            [0.0, 10.0, 0.0],
+        );
+        // the middle item carries the new one, and passes it on to both items below by contact area
+        let added_loads = get_added_top_loads(&bin, &[50.0, 10.0, 100.0], &[0.0, 20.0, 0.0], 40.0);
+        assert_eq!(added_loads, [20.0, 20.0, 40.0]);
+    }
+
+    #[test]
+    fn items_below_carry_at_most_their_top_load() {
+        let items = [
+            get_item("carton", [100.0, 10.0, 100.0], 1.0, Some(25.0)),
+            get_item("first", [100.0, 10.0, 100.0], 20.0, None),
+            get_item("second", [100.0, 10.0, 100.0], 20.0, None),
+        ];
+        let mut bin = get_bin();
+        let unpacked = pack_items_into_bin(
+            &mut bin,
+            &items,
+            &PackingOptions::default(),
+            0,
+            &SolverMonitor::none(),
+        );
+        assert_eq!(unpacked.len(), 1);
+        assert_eq!(unpacked[0].0.name, "second@box@0");
+        assert_eq!(unpacked[0].1, UnpackedReason::TopLoadExceeded);
+        assert_eq!(bin.packed_items[0].top_load, 20.0);
+    }
+}
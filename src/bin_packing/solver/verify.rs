@@ -0,0 +1,223 @@
+/*
+    Independent check of a packed bin, e.g. a plan edited by hand or packed by an older solver.
+    The plan is rebuilt from the floor up with the checks the solver applies while packing,
+    every broken constraint is reported instead of stopping at the first one.
+*/
+use crate::bin_packing::bin::Bin;
+use crate::bin_packing::item::Item;
+use crate::bin_packing::options::PackingOptions;
+use crate::bin_packing::result::Violation;
+use crate::bin_packing::solver::{balance, check_intersection, get_allowed_rotations, support};
+
+// rounding allowance for positions and loads of hand edited plans
+const TOLERANCE: f32 = 1e-3;
+
+fn check_orientation(item: &Item, violations: &mut Vec<Violation>) {
+    let rotation = item.rotation.as_str();
+    let known = get_allowed_rotations(true).contains(&rotation);
+    let upright = get_allowed_rotations(false).contains(&rotation);
+    if !known || (item.orientable == Some(false) && !upright) {
+        violations.push(Violation::OrientationForbidden {
+            item_name: item.name.clone(),
+            rotation: item.rotation.clone(),
+        });
+    }
+}
+
+fn is_inside_bin(bin: &Bin, item: &Item) -> bool {
+    let dimension = item.get_rotated_dimension(&item.rotation);
+    let bin_dimension = [bin.width, bin.height, bin.depth];
+    (0..3).all(|axis| {
+        item.position[axis] >= -TOLERANCE
+            && item.position[axis] + dimension[axis] <= bin_dimension[axis] + TOLERANCE
+    })
+}
+
+/*
+    Checks the items placed into an empty bin.
+    Pass the options the plan was packed with, items resting on nothing are reported with any min support ratio.
+*/
+pub fn verify_packed_bin(
+    mut bin: Bin,
+    items: Vec<Item>,
+    options: &PackingOptions,
+) -> Vec<Violation> {
+    let mut violations = Vec::new();
+
+    for (index, item) in items.iter().enumerate() {
+        check_orientation(item, &mut violations);
+        if !is_inside_bin(&bin, item) {
+            violations.push(Violation::OutOfBounds {
+                item_name: item.name.clone(),
+            });
+        }
+        let dimension = item.get_rotated_dimension(&item.rotation);
+        for other in &items[index + 1..] {
+            if check_intersection(other, &dimension, &item.position) {
+                violations.push(Violation::ItemOverlap {
+                    item_name: item.name.clone(),
+                    other_item_name: other.name.clone(),
+                });
+            }
+        }
+    }
+
+    // Lower items first, so every item finds the items it rests on already in the bin
+    let mut items = items;
+    items.sort_by(|a, b| a.position[1].total_cmp(&b.position[1]));
+    for item in items {
+        let dimension = item.get_rotated_dimension(&item.rotation);
+        let support_ratio = support::get_support_ratio(&bin, &dimension, &item.position);
+        if !support::is_supported(support_ratio, options.min_support_ratio) {
+            violations.push(Violation::Unsupported {
+                item_name: item.name.clone(),
+                support_ratio,
+            });
+        }
+        let added_loads =
+            support::get_added_top_loads(&bin, &dimension, &item.position, item.weight);
+        for (item_in_bin, added_load) in bin.packed_items.iter_mut().zip(added_loads) {
+            item_in_bin.top_load += added_load;
+        }
+        bin.axle_loads =
+            balance::get_axle_loads_with_item(&bin, &dimension, &item.position, item.weight);
+        bin.packed_items.push(item);
+    }
+
+    for item in &bin.packed_items {
+        if let Some(max_top_load) = item.get_top_load_limit() {
+            if item.top_load > max_top_load + TOLERANCE {
+                violations.push(Violation::TopLoadExceeded {
+                    item_name: item.name.clone(),
+                    top_load: item.top_load,
+                    max_top_load,
+                });
+            }
+        }
+    }
+
+    let weight = bin.get_packed_items_weight();
+    if weight > bin.max_weight + TOLERANCE {
+        violations.push(Violation::OverWeight {
+            weight,
+            max_weight: bin.max_weight,
+        });
+    }
+    violations.extend(balance::get_axle_violations(&bin, TOLERANCE));
+    violations.extend(balance::get_plan_violations(&bin));
+    violations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bin_packing::api;
+    use crate::bin_packing::bin::RawBin;
+    use crate::bin_packing::item::{RawItem, RawPackedItem};
+    use crate::bin_packing::options::PlacementPolicy;
+    use crate::bin_packing::order::RawOrder;
+    use crate::bin_packing::progress::SolverMonitor;
+    use crate::bin_packing::solver::random::Random;
+
+    fn get_raw_bin() -> RawBin {
+        RawBin {
+            name: "truck".to_string(),
+            depth: 300.0,
+            width: 200.0,
+            height: 200.0,
+            max_weight: 5000.0,
+            cog_envelope: None,
+            axles: None,
+        }
+    }
+
+    fn get_random_orders(random: &mut Random) -> Vec<RawOrder> {
+        (0..6)
+            .map(|index| RawOrder {
+                name: format!("order{index}"),
+                items: vec![RawItem {
+                    name: "box".to_string(),
+                    quantity: 1 + random.next_index(6) as i32,
+                    depth: 20.0 + random.next_f32() * 80.0,
+                    width: 20.0 + random.next_f32() * 80.0,
+                    height: 20.0 + random.next_f32() * 80.0,
+                    weight: 10.0 + random.next_f32() * 90.0,
+                    max_top_load: (random.next_index(2) == 0).then_some(200.0),
+                }],
+                orientable: Some(random.next_index(2) == 0),
+                stackable: Some(random.next_index(4) != 0),
+            })
+            .collect()
+    }
+
+    // Items of a packed bin as they are given back for verification, without the loads found by the solver
+    fn get_plan_items(bin: &Bin) -> Vec<Item> {
+        bin.packed_items
+            .iter()
+            .map(|item| Item {
+                top_load: 0.0,
+                ..item.clone()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn solver_plans_have_no_violations() {
+        let mut random = Random::new(1);
+        for policy in [PlacementPolicy::FirstFit, PlacementPolicy::BestContactArea] {
+            for _ in 0..10 {
+                let raw_bins = [get_raw_bin()];
+                let options = PackingOptions {
+                    placement_policy: policy,
+                    min_support_ratio: 0.5,
+                    ..Default::default()
+                };
+                let result = api::get_smallest_fitting_bin_for_order_list(
+                    &get_random_orders(&mut random),
+                    &raw_bins,
+                    &options,
+                    &SolverMonitor::none(),
+                )
+                .unwrap();
+                let packed_bin = result.bin.unwrap();
+                assert!(!packed_bin.packed_items.is_empty());
+                let violations = verify_packed_bin(
+                    raw_bins[0].convert_to_bin(),
+                    get_plan_items(&packed_bin),
+                    &options,
+                );
+                assert_eq!(violations, Vec::new());
+            }
+        }
+    }
+
+    #[test]
+    fn item_resting_on_nothing_is_unsupported() {
+        let raw_bin = get_raw_bin();
+        let item = RawPackedItem {
+            name: "box".to_string(),
+            depth: 50.0,
+            width: 50.0,
+            height: 50.0,
+            weight: 10.0,
+            max_top_load: None,
+            orientable: None,
+            stackable: None,
+            rotation: "RT_WHD".to_string(),
+            position: [0.0, 60.0, 0.0],
+        }
+        .convert_to_item();
+        let violations = verify_packed_bin(
+            raw_bin.convert_to_bin(),
+            vec![item.clone()],
+            &PackingOptions::default(),
+        );
+        assert_eq!(
+            violations,
+            vec![Violation::Unsupported {
+                item_name: item.name,
+                support_ratio: 0.0,
+            }]
+        );
+    }
+}
@@ -0,0 +1,430 @@
+/*
+    Validation of the input before orders and bins are converted for the solver.
+    Every problem is collected with the path of its field, e.g. "orders[3].items[1].width",
+    so a form can show all of them at once instead of one per submit.
+*/
+use crate::bin_packing::bin::{DLBinWithPackedOrders, RawBin};
+use crate::bin_packing::error::PackingError;
+use crate::bin_packing::item::RawPackedItem;
+use crate::bin_packing::options::{FloorPlanOptions, PackingOptions};
+use crate::bin_packing::order::{DimensionLessOrder, RawOrder};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+// Problem found in the input, serialized for the caller with a readable message
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct ValidationIssue {
+    pub code: &'static str,
+    pub path: Option<String>,
+    pub message: String,
+}
+
+impl ValidationIssue {
+    pub fn new(error: &PackingError) -> ValidationIssue {
+        ValidationIssue {
+            code: error.get_code(),
+            path: error.get_path().map(str::to_string),
+            message: error.to_string(),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct Validator {
+    pub issues: Vec<PackingError>,
+}
+
+impl Validator {
+    pub fn new() -> Validator {
+        Validator::default()
+    }
+
+    // First issue as error, the api stops at invalid input
+    pub fn into_result(self) -> Result<(), PackingError> {
+        match self.issues.into_iter().next() {
+            Some(issue) => Err(issue),
+            None => Ok(()),
+        }
+    }
+
+    // Positive finite number, e.g. a length
+    fn check_dimension(&mut self, path: String, value: f64) {
+        if !(value.is_finite() && value > 0.0) {
+            self.issues
+                .push(PackingError::InvalidDimension { path, value });
+        }
+    }
+
+    // Non-negative finite number, e.g. a weight
+    fn check_value(&mut self, path: String, value: f64) {
+        if !(value.is_finite() && value >= 0.0) {
+            self.issues.push(PackingError::InvalidValue { path, value });
+        }
+    }
+
+    fn check_not_empty<T>(&mut self, path: &str, list: &[T]) {
+        if list.is_empty() {
+            self.issues.push(PackingError::EmptyInput {
+                path: path.to_string(),
+            });
+        }
+    }
+
+    // Share between 0.0 and 1.0, e.g. a ratio of an option
+    fn check_share(&mut self, path: String, value: f32) {
+        if !(0.0..=1.0).contains(&value) {
+            self.issues.push(PackingError::InfeasibleConstraint {
+                path,
+                message: "must be a share between 0.0 and 1.0".to_string(),
+            });
+        }
+    }
+
+    // Every name after its first use is reported, path gives the path of the name at an index
+    fn check_unique_names<'a>(
+        &mut self,
+        names: impl Iterator<Item = &'a str>,
+        path: impl Fn(usize) -> String,
+    ) {
+        let mut first_indices: BTreeMap<&str, usize> = BTreeMap::new();
+        for (index, name) in names.enumerate() {
+            if let Some(&first_index) = first_indices.get(name) {
+                self.issues.push(PackingError::DuplicateName {
+                    path: path(index),
+                    name: name.to_string(),
+                    first_path: path(first_index),
+                });
+            } else {
+                first_indices.insert(name, index);
+            }
+        }
+    }
+
+    // Item names are built as "order@item@index", so the separator may not be part of a name
+    fn check_name(&mut self, path: String, name: &str) {
+        if name.is_empty() || name.contains('@') {
+            self.issues.push(PackingError::InvalidName {
+                path,
+                name: name.to_string(),
+            });
+        }
+    }
+
+    pub fn validate_raw_orders(&mut self, raw_orders: &[RawOrder]) {
+        self.check_not_empty("orders", raw_orders);
+        self.check_unique_names(raw_orders.iter().map(|order| order.name.as_str()), |i| {
+            format!("orders[{}].name", i)
+        });
+        for (i, order) in raw_orders.iter().enumerate() {
+            self.check_name(format!("orders[{}].name", i), &order.name);
+            self.check_not_empty(&format!("orders[{}].items", i), &order.items);
+            self.check_unique_names(order.items.iter().map(|item| item.name.as_str()), |j| {
+                format!("orders[{}].items[{}].name", i, j)
+            });
+            for (j, item) in order.items.iter().enumerate() {
+                let path = |field: &str| format!("orders[{}].items[{}].{}", i, j, field);
+                self.check_name(path("name"), &item.name);
+                self.check_dimension(path("width"), item.width as f64);
+                self.check_dimension(path("depth"), item.depth as f64);
+                self.check_dimension(path("height"), item.height as f64);
+                self.check_value(path("weight"), item.weight as f64);
+                self.check_value(path("quantity"), item.quantity as f64);
+                if let Some(max_top_load) = item.max_top_load {
+                    self.check_value(path("max_top_load"), max_top_load as f64);
+                }
+            }
+        }
+    }
+
+    // Positions may lie outside the bin, the plan verifier reports that, but must be numbers
+    pub fn validate_raw_packed_items(&mut self, raw_items: &[RawPackedItem]) {
+        self.check_unique_names(raw_items.iter().map(|item| item.name.as_str()), |i| {
+            format!("items[{}].name", i)
+        });
+        for (i, item) in raw_items.iter().enumerate() {
+            let path = |field: &str| format!("items[{}].{}", i, field);
+            self.check_dimension(path("width"), item.width as f64);
+            self.check_dimension(path("depth"), item.depth as f64);
+            self.check_dimension(path("height"), item.height as f64);
+            self.check_value(path("weight"), item.weight as f64);
+            if let Some(max_top_load) = item.max_top_load {
+                self.check_value(path("max_top_load"), max_top_load as f64);
+            }
+            for (axis, coordinate) in item.position.iter().enumerate() {
+                if !coordinate.is_finite() {
+                    self.issues.push(PackingError::InvalidValue {
+                        path: format!("items[{}].position[{}]", i, axis),
+                        value: *coordinate as f64,
+                    });
+                }
+            }
+        }
+    }
+
+    pub fn validate_raw_bin(&mut self, raw_bin: &RawBin, path: &str) {
+        let field_path = |field: &str| format!("{}.{}", path, field);
+        self.check_dimension(field_path("width"), raw_bin.width as f64);
+        self.check_dimension(field_path("depth"), raw_bin.depth as f64);
+        self.check_dimension(field_path("height"), raw_bin.height as f64);
+        self.check_value(field_path("max_weight"), raw_bin.max_weight as f64);
+        if let Some(envelope) = &raw_bin.cog_envelope {
+            for (field, deviation) in [
+                ("width", envelope.width),
+                ("height", envelope.height),
+                ("depth", envelope.depth),
+            ] {
+                if deviation.is_some_and(|deviation| deviation.is_nan() || deviation < 0.0) {
+                    self.issues.push(PackingError::InfeasibleConstraint {
+                        path: field_path(&format!("cog_envelope.{}", field)),
+                        message: "allowed deviation must be a non-negative share".to_string(),
+                    });
+                }
+            }
+        }
+        for (i, axle) in raw_bin.axles.iter().flatten().enumerate() {
+            self.check_value(
+                field_path(&format!("axles[{}].position", i)),
+                axle.position as f64,
+            );
+            self.check_value(
+                field_path(&format!("axles[{}].max_load", i)),
+                axle.max_load as f64,
+            );
+        }
+    }
+
+    pub fn validate_raw_bins(&mut self, raw_bins: &[RawBin]) {
+        self.check_not_empty("bins", raw_bins);
+        self.check_unique_names(raw_bins.iter().map(|bin| bin.name.as_str()), |i| {
+            format!("bins[{}].name", i)
+        });
+        for (i, raw_bin) in raw_bins.iter().enumerate() {
+            self.validate_raw_bin(raw_bin, &format!("bins[{}]", i));
+        }
+    }
+
+    pub fn validate_packing_options(&mut self, options: &PackingOptions) {
+        self.check_share(
+            "options.min_support_ratio".to_string(),
+            options.min_support_ratio,
+        );
+        if let Some(units_per_meter) = options.units_per_meter {
+            self.check_dimension(
+                "options.units_per_meter".to_string(),
+                units_per_meter as f64,
+            );
+        }
+        if let Some(improvement) = &options.improvement {
+            self.check_share(
+                "options.improvement.cooling_rate".to_string(),
+                improvement.cooling_rate,
+            );
+            self.check_value(
+                "options.improvement.initial_temperature".to_string(),
+                improvement.initial_temperature as f64,
+            );
+            if let Some(time_limit_ms) = improvement.time_limit_ms {
+                self.check_value(
+                    "options.improvement.time_limit_ms".to_string(),
+                    time_limit_ms,
+                );
+            }
+        }
+    }
+
+    pub fn validate_floor_plan_options(&mut self, options: &FloorPlanOptions) {
+        if let Some(units_per_meter) = options.units_per_meter {
+            self.check_dimension(
+                "options.units_per_meter".to_string(),
+                units_per_meter as f64,
+            );
+        }
+    }
+
+    pub fn validate_dimensionless_orders(&mut self, orders: &[DimensionLessOrder], path: &str) {
+        self.check_unique_names(orders.iter().map(|order| order.id.as_str()), |i| {
+            format!("{}[{}].id", path, i)
+        });
+        for (i, order) in orders.iter().enumerate() {
+            let field_path = |field: &str| format!("{}[{}].{}", path, i, field);
+            self.check_value(field_path("weight"), order.weight);
+            if let Some(volume) = order.volume {
+                self.check_value(field_path("volume"), volume);
+            }
+            if let Some(value) = order.value {
+                self.check_value(field_path("value"), value);
+            }
+            for (resource, usage) in &order.resources {
+                self.check_value(field_path(&format!("resources.{}", resource)), *usage);
+            }
+        }
+    }
+
+    pub fn validate_dimensionless_bins(&mut self, bins: &[DLBinWithPackedOrders], path: &str) {
+        self.check_not_empty(path, bins);
+        self.check_unique_names(bins.iter().map(|bin| bin.id.as_str()), |i| {
+            format!("{}[{}].id", path, i)
+        });
+        for (i, bin) in bins.iter().enumerate() {
+            let field_path = |field: &str| format!("{}[{}].{}", path, i, field);
+            self.check_value(field_path("max_weight"), bin.max_weight);
+            if let Some(max_volume) = bin.max_volume {
+                self.check_value(field_path("max_volume"), max_volume);
+            }
+            for (resource, capacity) in &bin.max_resources {
+                self.check_value(
+                    field_path(&format!("max_resources.{}", resource)),
+                    *capacity,
+                );
+            }
+            if let Some(density) = bin.density {
+                self.check_dimension(field_path("density"), density);
+            }
+            if let Some(cost) = bin.cost {
+                self.check_value(field_path("cost"), cost);
+            }
+            self.validate_dimensionless_orders(&bin.packed_orders, &field_path("packed_orders"));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bin_packing::item::RawItem;
+
+    fn get_raw_item(name: &str) -> RawItem {
+        RawItem {
+            name: name.to_string(),
+            quantity: 1,
+            depth: 10.0,
+            width: 10.0,
+            height: 10.0,
+            weight: 1.0,
+            max_top_load: None,
+        }
+    }
+
+    fn get_raw_orders(count: usize) -> Vec<RawOrder> {
+        (0..count)
+            .map(|index| RawOrder {
+                name: format!("order{index}"),
+                items: vec![get_raw_item("a"), get_raw_item("b")],
+                orientable: None,
+                stackable: None,
+            })
+            .collect()
+    }
+
+    fn get_raw_bin(name: &str) -> RawBin {
+        RawBin {
+            name: name.to_string(),
+            depth: 100.0,
+            width: 100.0,
+            height: 100.0,
+            max_weight: 100.0,
+            cog_envelope: None,
+            axles: None,
+        }
+    }
+
+    fn get_issues(raw_orders: &[RawOrder], raw_bins: &[RawBin]) -> Vec<(&'static str, String)> {
+        let mut validator = Validator::new();
+        validator.validate_raw_orders(raw_orders);
+        validator.validate_raw_bins(raw_bins);
+        validator
+            .issues
+            .iter()
+            .map(|issue| (issue.get_code(), issue.get_path().unwrap().to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn valid_input_has_no_issues() {
+        assert!(get_issues(&get_raw_orders(4), &[get_raw_bin("truck")]).is_empty());
+    }
+
+    #[test]
+    fn every_issue_is_reported_with_its_path() {
+        let mut raw_orders = get_raw_orders(4);
+        raw_orders[3].items[1].width = -1.0;
+        raw_orders[2].items[0].weight = f32::NAN;
+        raw_orders[1].items[1].quantity = -2;
+        raw_orders[0].items[0].name = "a@b".to_string();
+        raw_orders[1].items[0].height = 0.0;
+        let issues = get_issues(&raw_orders, &[get_raw_bin("truck")]);
+        assert_eq!(
+            issues,
+            [
+                ("invalid_name", "orders[0].items[0].name".to_string()),
+                ("invalid_dimension", "orders[1].items[0].height".to_string()),
+                ("invalid_value", "orders[1].items[1].quantity".to_string()),
+                ("invalid_value", "orders[2].items[0].weight".to_string()),
+                ("invalid_dimension", "orders[3].items[1].width".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn duplicate_names_point_to_their_first_use() {
+        let mut raw_orders = get_raw_orders(3);
+        raw_orders[2].name = "order0".to_string();
+        raw_orders[1].items[1].name = "a".to_string();
+        let mut validator = Validator::new();
+        validator.validate_raw_orders(&raw_orders);
+        validator.validate_raw_bins(&[get_raw_bin("truck"), get_raw_bin("truck")]);
+        assert_eq!(
+            validator.issues,
+            [
+                PackingError::DuplicateName {
+                    path: "orders[2].name".to_string(),
+                    name: "order0".to_string(),
+                    first_path: "orders[0].name".to_string(),
+                },
+                PackingError::DuplicateName {
+                    path: "orders[1].items[1].name".to_string(),
+                    name: "a".to_string(),
+                    first_path: "orders[1].items[0].name".to_string(),
+                },
+                PackingError::DuplicateName {
+                    path: "bins[1].name".to_string(),
+                    name: "truck".to_string(),
+                    first_path: "bins[0].name".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn empty_lists_are_reported() {
+        let mut raw_orders = get_raw_orders(1);
+        raw_orders[0].items.clear();
+        assert_eq!(
+            get_issues(&raw_orders, &[]),
+            [
+                ("empty_input", "orders[0].items".to_string()),
+                ("empty_input", "bins".to_string()),
+            ]
+        );
+        assert_eq!(
+            get_issues(&[], &[get_raw_bin("truck")]),
+            [("empty_input", "orders".to_string())]
+        );
+    }
+
+    #[test]
+    fn first_issue_is_returned_as_error() {
+        let mut raw_orders = get_raw_orders(2);
+        raw_orders[1].items[0].depth = f32::INFINITY;
+        raw_orders[1].items[1].depth = 0.0;
+        let mut validator = Validator::new();
+        validator.validate_raw_orders(&raw_orders);
+        assert_eq!(
+            validator.into_result(),
+            Err(PackingError::InvalidDimension {
+                path: "orders[1].items[0].depth".to_string(),
+                value: f64::INFINITY,
+            })
+        );
+    }
+}
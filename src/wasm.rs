@@ -0,0 +1,318 @@
+/*
+    Javascript bindings of the packing engine, built with the wasm feature.
+    Every function converts the javascript values, calls the rust api and converts the result back.
+*/
+use crate::bin_packing::api;
+use crate::bin_packing::bin::{DLBinWithPackedOrders, RawBin};
+use crate::bin_packing::error::PackingError;
+use crate::bin_packing::item::RawPackedItem;
+use crate::bin_packing::options::{DimensionLessOptions, FloorPlanOptions, PackingOptions};
+use crate::bin_packing::order::{DimensionLessOrder, RawOrder};
+use crate::bin_packing::progress::{Cancellation, ProgressEvent, ProgressSink, SolverMonitor};
+use js_sys::{Function, Reflect};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+// Progress events go to the js update function as plain objects, e.g. { type: "item_placed", ... }
+struct JsProgressSink<'a> {
+    update_function: &'a Function,
+}
+
+impl ProgressSink for JsProgressSink<'_> {
+    fn report(&self, event: &ProgressEvent) {
+        if let Ok(js_event) = serde_wasm_bindgen::to_value(event) {
+            _ = self.update_function.call1(&JsValue::NULL, &js_event);
+        }
+    }
+}
+
+// The run is cancelled once the optional js function returns a truthy value
+struct JsCancellation {
+    is_cancelled_function: Option<Function>,
+}
+
+impl Cancellation for JsCancellation {
+    fn is_cancelled(&self) -> bool {
+        self.is_cancelled_function.as_ref().is_some_and(|function| {
+            function
+                .call0(&JsValue::NULL)
+                .is_ok_and(|value| value.is_truthy())
+        })
+    }
+}
+
+/*
+    Errors reach js as Error objects with a code and the path of the field that caused them,
+    e.g. { message: "...", code: "invalid_dimension", path: "orders[3].items[1].width" }
+*/
+fn to_js_error(error: PackingError) -> JsValue {
+    let js_error = js_sys::Error::new(&error.to_string());
+    _ = Reflect::set(&js_error, &"code".into(), &error.get_code().into());
+    let path = error.get_path().map_or(JsValue::NULL, JsValue::from);
+    _ = Reflect::set(&js_error, &"path".into(), &path);
+    js_error.into()
+}
+
+// Path of the field below the argument, e.g. "orders" and "[3].items[1].width"
+fn get_field_path(path: &str, field_path: &serde_path_to_error::Path) -> String {
+    match field_path.to_string().as_str() {
+        "." => path.to_string(),
+        field_path if field_path.starts_with('[') => format!("{path}{field_path}"),
+        field_path => format!("{path}.{field_path}"),
+    }
+}
+
+// Reads an argument, path names the argument in the error, followed by the field that could not be read
+fn from_js_value<T: DeserializeOwned>(value: JsValue, path: &str) -> Result<T, JsValue> {
+    let deserializer = serde_wasm_bindgen::Deserializer::from(value);
+    serde_path_to_error::deserialize(deserializer).map_err(|error| {
+        to_js_error(PackingError::InvalidInput {
+            path: get_field_path(path, error.path()),
+            message: error.into_inner().to_string(),
+        })
+    })
+}
+
+// Options are optional, missing fields take their defaults
+fn from_js_options<T: DeserializeOwned + Default>(value: JsValue) -> Result<T, JsValue> {
+    from_js_value::<Option<T>>(value, "options").map(Option::unwrap_or_default)
+}
+
+// Resource maps go back to js as plain objects
+fn to_js_value<T: Serialize>(result: Result<T, PackingError>) -> Result<JsValue, JsValue> {
+    let serializer = serde_wasm_bindgen::Serializer::new().serialize_maps_as_objects(true);
+    result
+        .and_then(|result| {
+            result
+                .serialize(&serializer)
+                .map_err(|error| PackingError::SerializationFailed {
+                    message: error.to_string(),
+                })
+        })
+        .map_err(to_js_error)
+}
+
+#[wasm_bindgen]
+pub fn test_wasm() -> String {
+    "WASM Loaded...".to_string()
+}
+
+/*
+    This function returns the smallest bin that holds all orders
+    js_update_function receives the progress events, js_is_cancelled is optional and polled while packing
+*/
+
+#[wasm_bindgen]
+pub fn get_smallest_fitting_bin_for_order_list(
+    js_orders: JsValue,
+    js_bins: JsValue,
+    js_update_function: &Function,
+    js_options: JsValue,
+    js_is_cancelled: Option<Function>,
+) -> Result<JsValue, JsValue> {
+    // raw orders are orders from frontend
+    let raw_orders: Vec<RawOrder> = from_js_value(js_orders, "orders")?;
+    // raw bins are bins from frontend
+    let raw_bins: Vec<RawBin> = from_js_value(js_bins, "bins")?;
+    let options: PackingOptions = from_js_options(js_options)?;
+
+    // if no bin holds all items, the result holds the fullest bin and the unpacked items
+    let result = api::get_smallest_fitting_bin_for_order_list(
+        &raw_orders,
+        &raw_bins,
+        &options,
+        &SolverMonitor::new(
+            &JsProgressSink {
+                update_function: js_update_function,
+            },
+            &JsCancellation {
+                is_cancelled_function: js_is_cancelled,
+            },
+        ),
+    );
+    to_js_value(result)
+}
+
+/*
+    This function is used to pack an order list into as many bins as needed
+    Bins from the bin list can be used more than once
+*/
+
+#[wasm_bindgen]
+pub fn pack_order_list_into_multiple_bins(
+    js_orders: JsValue,
+    js_bins: JsValue,
+    js_update_function: &Function,
+    js_options: JsValue,
+    js_is_cancelled: Option<Function>,
+) -> Result<JsValue, JsValue> {
+    let raw_orders: Vec<RawOrder> = from_js_value(js_orders, "orders")?;
+    let raw_bins: Vec<RawBin> = from_js_value(js_bins, "bins")?;
+    let options: PackingOptions = from_js_options(js_options)?;
+
+    let result = api::pack_order_list_into_multiple_bins(
+        &raw_orders,
+        &raw_bins,
+        &options,
+        &SolverMonitor::new(
+            &JsProgressSink {
+                update_function: js_update_function,
+            },
+            &JsCancellation {
+                is_cancelled_function: js_is_cancelled,
+            },
+        ),
+    );
+    to_js_value(result)
+}
+
+/*
+    This function is used to pack max additional DIMENSION-LESS orders into DIMENSION-LESS bins that already have packed orders
+*/
+
+#[wasm_bindgen]
+pub fn pack_max_dimensionless_orders(
+    dl_bins_with_packed_orders: JsValue,
+    additional_orders: JsValue, // must be dimensionless orders
+    js_options: JsValue,
+) -> Result<JsValue, JsValue> {
+    // these bins may have packed orders
+    let dl_bins_with_packed_orders: Vec<DLBinWithPackedOrders> =
+        from_js_value(dl_bins_with_packed_orders, "bins")?;
+    let additional_orders: Vec<DimensionLessOrder> = from_js_value(additional_orders, "orders")?;
+
+    let options: DimensionLessOptions = from_js_options(js_options)?;
+
+    let result =
+        api::pack_max_dimensionless_orders(dl_bins_with_packed_orders, additional_orders, &options);
+    to_js_value(result)
+}
+
+/*
+    This function lists alternative selections of additional DIMENSION-LESS orders for every bin,
+    e.g. the heaviest and the most volume efficient load
+*/
+
+#[wasm_bindgen]
+pub fn get_dimensionless_alternatives(
+    dl_bins_with_packed_orders: JsValue,
+    additional_orders: JsValue,
+    js_options: JsValue,
+) -> Result<JsValue, JsValue> {
+    let dl_bins_with_packed_orders: Vec<DLBinWithPackedOrders> =
+        from_js_value(dl_bins_with_packed_orders, "bins")?;
+    let additional_orders: Vec<DimensionLessOrder> = from_js_value(additional_orders, "orders")?;
+    let options: DimensionLessOptions = from_js_options(js_options)?;
+
+    let result = api::get_dimensionless_alternatives(
+        dl_bins_with_packed_orders,
+        additional_orders,
+        &options,
+    );
+    to_js_value(result)
+}
+
+/*
+    This function chooses which empty DIMENSION-LESS bins to open for a list of DIMENSION-LESS orders,
+    aiming for the lowest total bin cost
+*/
+
+#[wasm_bindgen]
+pub fn pack_orders_into_dimensionless_bins(
+    dl_bin_types: JsValue,
+    orders: JsValue, // must be dimensionless orders
+    js_options: JsValue,
+) -> Result<JsValue, JsValue> {
+    let dl_bin_types: Vec<DLBinWithPackedOrders> = from_js_value(dl_bin_types, "bin_types")?;
+    let orders: Vec<DimensionLessOrder> = from_js_value(orders, "orders")?;
+    let options: DimensionLessOptions = from_js_options(js_options)?;
+
+    let result = api::pack_orders_into_dimensionless_bins(dl_bin_types, orders, &options);
+    to_js_value(result)
+}
+
+/*
+    This function places the pallets of an order list onto the floor of a truck, stacking pallets
+    with the same footprint, and reports the loading meters taken
+*/
+
+#[wasm_bindgen]
+pub fn plan_pallet_floor(
+    js_orders: JsValue,
+    js_bin: JsValue,
+    js_options: JsValue,
+) -> Result<JsValue, JsValue> {
+    let raw_orders: Vec<RawOrder> = from_js_value(js_orders, "orders")?;
+    let raw_bin: RawBin = from_js_value(js_bin, "bin")?;
+    let options: FloorPlanOptions = from_js_options(js_options)?;
+
+    let result = api::plan_pallet_floor(&raw_orders, &raw_bin, &options);
+    to_js_value(result)
+}
+
+/*
+    This function checks orders and bins before they are submitted for packing
+    Returns every problem found as { code, path, message }, an empty list for valid input
+*/
+
+#[wasm_bindgen]
+pub fn validate_input(js_orders: JsValue, js_bins: JsValue) -> Result<JsValue, JsValue> {
+    let raw_orders: Vec<RawOrder> = from_js_value(js_orders, "orders")?;
+    let raw_bins: Vec<RawBin> = from_js_value(js_bins, "bins")?;
+
+    to_js_value(Ok(api::validate_input(&raw_orders, &raw_bins)))
+}
+
+/*
+    This function checks a packed bin, e.g. a plan edited by hand or packed by an older version
+    Items need their original dimensions next to position and rotation
+    Returns every broken constraint, an empty list for a valid plan
+*/
+
+#[wasm_bindgen]
+pub fn verify_plan(
+    js_bin: JsValue,
+    js_items: JsValue,
+    js_options: JsValue,
+) -> Result<JsValue, JsValue> {
+    let raw_bin: RawBin = from_js_value(js_bin, "bin")?;
+    let raw_items: Vec<RawPackedItem> = from_js_value(js_items, "items")?;
+    let options: PackingOptions = from_js_options(js_options)?;
+
+    to_js_value(api::verify_plan(&raw_bin, &raw_items, &options))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn get_error_path<T: DeserializeOwned>(value: serde_json::Value, path: &str) -> String {
+        let error = serde_path_to_error::deserialize::<_, T>(value)
+            .err()
+            .unwrap();
+        get_field_path(path, error.path())
+    }
+
+    #[test]
+    fn wrong_types_are_reported_with_their_field_path() {
+        let item = json!({ "name": "box", "quantity": 1, "depth": 1.0, "width": 1.0, "height": 1.0, "weight": 1.0 });
+        let mut wrong_item = item.clone();
+        wrong_item["width"] = json!("wide");
+        let orders = json!([
+            { "name": "first", "items": [item] },
+            { "name": "second", "items": [item, wrong_item] },
+        ]);
+        assert_eq!(
+            get_error_path::<Vec<RawOrder>>(orders, "orders"),
+            "orders[1].items[1].width"
+        );
+        let options = json!({ "placement_policy": "somewhere" });
+        assert_eq!(
+            get_error_path::<PackingOptions>(options, "options"),
+            "options.placement_policy"
+        );
+        assert_eq!(get_error_path::<Vec<RawBin>>(json!(3), "bins"), "bins");
+    }
+}